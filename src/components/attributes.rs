@@ -1,13 +1,84 @@
 use specs::prelude::*;
 use specs_derive::*;
 
-#[derive(Component)]
+#[derive(Component, Clone, Debug)]
 pub struct SingleStat {
     pub base: i32,
     pub bonus: i32,
     pub modifiers: i32
 }
 
+impl SingleStat {
+    pub fn new(base: i32) -> SingleStat {
+        SingleStat{ base, bonus: 0, modifiers: 0 }
+    }
+
+    pub fn value(&self) -> i32 {
+        self.base + self.bonus + self.modifiers
+    }
+}
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Attribute {
+    Might, Agility, Wits, Vigor
+}
+
+pub const ATTRIBUTES : [Attribute; 4] = [Attribute::Might, Attribute::Agility, Attribute::Wits, Attribute::Vigor];
+
+impl Attribute {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Attribute::Might => "Might",
+            Attribute::Agility => "Agility",
+            Attribute::Wits => "Wits",
+            Attribute::Vigor => "Vigor"
+        }
+    }
+}
+
+// Might drives attack, agility evade, wits perception and stealth, vigor max hp.
+// Bonus comes from what is worn, modifiers from the conditions suffered
+#[derive(Component, Clone, Debug)]
+pub struct Attributes {
+    pub might: SingleStat,
+    pub agility: SingleStat,
+    pub wits: SingleStat,
+    pub vigor: SingleStat
+}
+
+impl Attributes {
+    pub fn new(might: i32, agility: i32, wits: i32, vigor: i32) -> Attributes {
+        Attributes{
+            might: SingleStat::new(might),
+            agility: SingleStat::new(agility),
+            wits: SingleStat::new(wits),
+            vigor: SingleStat::new(vigor)
+        }
+    }
+
+    pub fn get(&self, attribute: Attribute) -> &SingleStat {
+        match attribute {
+            Attribute::Might => &self.might,
+            Attribute::Agility => &self.agility,
+            Attribute::Wits => &self.wits,
+            Attribute::Vigor => &self.vigor
+        }
+    }
+
+    pub fn get_mut(&mut self, attribute: Attribute) -> &mut SingleStat {
+        match attribute {
+            Attribute::Might => &mut self.might,
+            Attribute::Agility => &mut self.agility,
+            Attribute::Wits => &mut self.wits,
+            Attribute::Vigor => &mut self.vigor
+        }
+    }
+}
+
+// Marks an entity whose derived stats are stale
+#[derive(Component, Debug)]
+pub struct RecalculateStats {}
+
 #[derive(Component)]
 pub struct SinglePoolStat {
     pub current: i32,
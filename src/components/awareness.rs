@@ -0,0 +1,30 @@
+use bracket_lib::terminal::Point;
+use specs::prelude::*;
+use specs_derive::*;
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum AwarenessState {
+    Asleep, Unaware, Suspicious, Hunting, LostTrack
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct Awareness{
+    pub state: AwarenessState,
+    pub last_seen: Option<Point>,
+    pub turns_in_state: i32,
+    pub perception: i32
+}
+
+impl Awareness {
+    pub fn set_state(&mut self, state: AwarenessState) {
+        if self.state != state {
+            self.state = state;
+            self.turns_in_state = 0;
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct Stealth{
+    pub skill: i32
+}
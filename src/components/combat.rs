@@ -1,28 +1,96 @@
 use specs::prelude::*;
 use specs_derive::*;
+use serde::Deserialize;
 
 #[derive(Component, Debug, Clone)]
 pub struct MeleeIntent{
     pub target: Entity
 }
 
+#[derive(PartialEq, Copy, Clone, Debug, Deserialize)]
+pub enum DamageType {
+    Physical, Fire, Cold, Poison, Arcane
+}
+
 #[derive(Component, Debug)]
 pub struct Damage{
-    pub amount: Vec<i32>
+    pub amount: Vec<(i32, DamageType)>,
+    pub source: Option<Entity>
 }
 
 impl Damage {
-    pub fn new(store: &mut WriteStorage<Damage>, target: Entity, amount: i32) {
+    // Whoever dealt the latest hit gets the credit if it turns out to be the last
+    pub fn new(store: &mut WriteStorage<Damage>, target: Entity, amount: i32, kind: DamageType, source: Option<Entity>) {
         if let Some(suffering) = store.get_mut(target) {
-            suffering.amount.push(amount);
+            suffering.amount.push((amount, kind));
+            if source.is_some() {
+                suffering.source = source;
+            }
         } else {
-            let damage = Damage { amount: vec![amount] };
+            let damage = Damage { amount: vec![(amount, kind)], source };
             store.insert(target, damage).expect("Unable to insert Damage intent");
         }
     }
 }
 
-#[derive(Component, Debug)]
+// Left on anything whose hp ran out this turn, until the dead are cleaned up
+#[derive(Component, Debug, Clone)]
+pub struct Dead{
+    pub killer: Option<Entity>,
+    pub cause: DamageType
+}
+
+#[derive(Component, Debug, Clone, Deserialize)]
 pub struct InflictsDamage{
-    pub amount: i32
+    pub amount: i32,
+    pub kind: DamageType
+}
+
+// Multipliers applied to incoming damage of each type: 0.0 is immune, 2.0 takes double
+#[derive(Component, Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Resistances{
+    pub physical: f32,
+    pub fire: f32,
+    pub cold: f32,
+    pub poison: f32,
+    pub arcane: f32
+}
+
+impl Default for Resistances {
+    fn default() -> Self {
+        Resistances{ physical: 1.0, fire: 1.0, cold: 1.0, poison: 1.0, arcane: 1.0 }
+    }
+}
+
+impl Resistances {
+    pub fn multiplier(&self, kind: DamageType) -> f32 {
+        match kind {
+            DamageType::Physical => self.physical,
+            DamageType::Fire => self.fire,
+            DamageType::Cold => self.cold,
+            DamageType::Poison => self.poison,
+            DamageType::Arcane => self.arcane
+        }
+    }
+}
+
+// What is left of a hit once the target's resistances have had their say
+pub fn resisted_damage(resistances: Option<&Resistances>, amount: i32, kind: DamageType) -> i32 {
+    match resistances {
+        None => amount,
+        Some(resistances) => (amount as f32 * resistances.multiplier(kind)).round() as i32
+    }
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct RangedIntent{
+    pub target: Entity
+}
+
+#[derive(Component, Debug, Clone, Deserialize)]
+pub struct RangedWeapon{
+    pub range: i32,
+    pub power: i32,
+    pub kind: DamageType
 }
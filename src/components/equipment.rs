@@ -0,0 +1,109 @@
+use std::ops::Deref;
+
+use specs::prelude::*;
+use specs::storage::MaskedStorage;
+use specs_derive::*;
+use serde::Deserialize;
+
+use super::Attribute;
+
+#[derive(PartialEq, Copy, Clone, Debug, Deserialize)]
+pub enum EquipmentSlot {
+    Melee, Shield, Head, Body, Ring
+}
+
+impl EquipmentSlot {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EquipmentSlot::Melee => "weapon",
+            EquipmentSlot::Shield => "shield",
+            EquipmentSlot::Head => "head",
+            EquipmentSlot::Body => "body",
+            EquipmentSlot::Ring => "ring"
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct Equippable{
+    pub slot: EquipmentSlot
+}
+
+// Worn items stay in their owner's pack, this only marks them as in use
+#[derive(Component, Debug, Clone)]
+pub struct Equipped{
+    pub owner: Entity,
+    pub slot: EquipmentSlot
+}
+
+// Each enchantment adds one to the item's power or armor
+#[derive(Component, Debug, Clone)]
+pub struct Enchantment{
+    pub level: i32
+}
+
+// Gear wears down in a fight, and a broken item gives nothing until repaired
+#[derive(Component, Debug, Clone)]
+pub struct Durability{
+    pub current: i32,
+    pub max: i32
+}
+
+// Stronger than it looks, and worse than it looks: stuck on whoever wears it until uncursed
+#[derive(Component, Debug, Clone)]
+pub struct CursedItem{}
+
+#[derive(Component, Debug, Clone)]
+pub struct EquipItemIntent{
+    pub item: Entity
+}
+
+#[derive(Component, Debug, Clone, Deserialize)]
+pub struct MeleeWeapon{
+    pub power: i32
+}
+
+#[derive(Component, Debug, Clone, Deserialize)]
+pub struct Wearable{
+    pub armor: i32
+}
+
+#[derive(Component, Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AttributeBonus{
+    pub might: i32,
+    pub agility: i32,
+    pub wits: i32,
+    pub vigor: i32
+}
+
+impl AttributeBonus {
+    pub fn get(&self, attribute: Attribute) -> i32 {
+        match attribute {
+            Attribute::Might => self.might,
+            Attribute::Agility => self.agility,
+            Attribute::Wits => self.wits,
+            Attribute::Vigor => self.vigor
+        }
+    }
+}
+
+// What the gear a slot holds is worth right now, once wear and enchantments are counted
+pub fn gear_value(base: i32, enchantment: Option<&Enchantment>, durability: Option<&Durability>) -> i32 {
+    if durability.is_some_and(|d| d.current < 1) { return 0; }
+    base + enchantment.map_or(0, |e| e.level)
+}
+
+pub fn melee_power<D: Deref<Target = MaskedStorage<Durability>>>(owner: Entity, equipped: &ReadStorage<Equipped>, weapons: &ReadStorage<MeleeWeapon>, enchantments: &ReadStorage<Enchantment>, durability: &Storage<Durability, D>) -> i32 {
+    (equipped, weapons, enchantments.maybe(), durability.maybe()).join()
+        .filter(|(e, _, _, _)| e.owner == owner)
+        .map(|(_, weapon, enchantment, durability)| gear_value(weapon.power, enchantment, durability))
+        .sum()
+}
+
+pub fn armor<D: Deref<Target = MaskedStorage<Durability>>>(owner: Entity, equipped: &ReadStorage<Equipped>, wearables: &ReadStorage<Wearable>, enchantments: &ReadStorage<Enchantment>, durability: &Storage<Durability, D>) -> i32 {
+    (equipped, wearables, enchantments.maybe(), durability.maybe()).join()
+        .filter(|(e, _, _, _)| e.owner == owner)
+        .map(|(_, wearable, enchantment, durability)| gear_value(wearable.armor, enchantment, durability))
+        .sum()
+}
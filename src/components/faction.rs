@@ -0,0 +1,12 @@
+use specs::prelude::*;
+use specs_derive::*;
+
+#[derive(Component, Debug, Clone)]
+pub struct Faction{
+    pub name: String
+}
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Reaction {
+    Attack, Ignore, Flee
+}
@@ -0,0 +1,40 @@
+use specs::prelude::*;
+use specs_derive::*;
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum HungerState {
+    WellFed, Normal, Hungry, Starving
+}
+
+impl HungerState {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HungerState::WellFed => "Well Fed",
+            HungerState::Normal => "Normal",
+            HungerState::Hungry => "Hungry",
+            HungerState::Starving => "Starving"
+        }
+    }
+
+    // How many turns each stage lasts before sliding into the next
+    pub fn duration(&self) -> i32 {
+        match self {
+            HungerState::WellFed => 200,
+            HungerState::Normal => 300,
+            HungerState::Hungry => 200,
+            HungerState::Starving => 0
+        }
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct HungerClock {
+    pub state: HungerState,
+    pub duration: i32
+}
+
+impl HungerClock {
+    pub fn new(state: HungerState) -> HungerClock {
+        HungerClock{ state, duration: state.duration() }
+    }
+}
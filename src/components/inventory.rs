@@ -1,6 +1,9 @@
 use bracket_lib::terminal::Point;
 use specs::prelude::*;
 use specs_derive::*;
+use serde::Deserialize;
+
+use super::Name;
 
 #[derive(Component, Debug)]
 pub struct Item{}
@@ -22,12 +25,104 @@ pub struct UseItemIntent{
     pub target : Option<Point>
 }
 
+// What a merchant would ask for it, before enchantments
+#[derive(Component, Debug)]
+pub struct Value{
+    pub gold: i32
+}
+
+// Coins on the floor, picked up straight into the hero's purse
+#[derive(Component, Debug)]
+pub struct Gold{
+    pub amount: i32
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct DropItemIntent{
+    pub item : Entity
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct ThrowItemIntent{
+    pub item : Entity,
+    pub target : Point
+}
+
 #[derive(Component, Debug)]
 pub struct Consumable{
-    pub charges: i32
+    pub charges: i32,
+    pub max_charges: i32
 }
 
+impl Consumable {
+    pub fn new(charges: i32) -> Consumable {
+        Consumable{ charges, max_charges: charges }
+    }
+}
+
+// Identical items carried together, one entity standing for the whole pile
 #[derive(Component, Debug)]
+pub struct Stackable{
+    pub quantity: i32
+}
+
+// Single-use items keep their plain name, anything with charges shows what is left
+pub fn item_label(name: &Name, consumable: Option<&Consumable>, stack: Option<&Stackable>) -> String {
+    let label = match consumable {
+        Some(consumable) if consumable.max_charges > 1 => format!("{} ({})", name.name, consumable.charges),
+        _ => name.name.clone()
+    };
+    match stack {
+        Some(stack) if stack.quantity > 1 => format!("{} x{}", label, stack.quantity),
+        _ => label
+    }
+}
+
+// The stack an owner already holds that the item would fold into, if any
+pub fn matching_stack(entities: &Entities, item: Entity, owner: Entity, items_owned: &WriteStorage<ItemOwned>, names: &ReadStorage<Name>, stacks: &WriteStorage<Stackable>) -> Option<Entity> {
+    stacks.get(item)?;
+    let name = names.get(item)?;
+    (entities, items_owned, names, stacks).join()
+        .find(|(other, owned, other_name, _)| *other != item && owned.owner == owner && other_name.name == name.name)
+        .map(|(other, _, _, _)| other)
+}
+
+#[derive(Component, Debug, Clone, Deserialize)]
 pub struct Heals {
     pub amount: i32
 }
+
+#[derive(Component, Debug)]
+pub struct RemovesCurse {}
+
+#[derive(Component, Debug)]
+pub struct Identifies {}
+
+#[derive(Component, Debug)]
+pub struct MagicMapper {}
+
+#[derive(Component, Debug)]
+pub struct TeleportSelf {}
+
+#[derive(Component, Debug)]
+pub struct ProvidesFood {}
+
+// Bursts over whoever it lands on when thrown, rather than just bouncing off
+#[derive(Component, Debug)]
+pub struct Shatters {}
+
+// Goes by its disguise until the hero works out the real name
+#[derive(Component, Debug)]
+pub struct Unidentified {
+    pub name: String
+}
+
+#[derive(Component, Debug, Clone, Deserialize)]
+pub struct Ranged {
+    pub range: i32
+}
+
+#[derive(Component, Debug, Clone, Deserialize)]
+pub struct AreaOfEffect {
+    pub radius: i32
+}
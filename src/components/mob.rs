@@ -8,3 +8,8 @@ pub struct Mob{}
 pub struct Name{
     pub name: String    
 }
+
+#[derive(Component, Debug, Clone)]
+pub struct GroupMember{
+    pub leader: Entity
+}
@@ -10,6 +10,13 @@ mod viewshed;
 mod inventory;
 mod particle;
 mod trigger;
+mod faction;
+mod awareness;
+mod status;
+mod upgrade;
+mod equipment;
+mod vendor;
+mod hunger;
 
 pub use position::Position;
 pub use renderable::Renderable;
@@ -22,4 +29,11 @@ pub use combat::*;
 pub use viewshed::*;
 pub use inventory::*;
 pub use particle::*;
-pub use trigger::*;
\ No newline at end of file
+pub use trigger::*;
+pub use faction::*;
+pub use awareness::*;
+pub use status::*;
+pub use upgrade::*;
+pub use equipment::*;
+pub use vendor::*;
+pub use hunger::*;
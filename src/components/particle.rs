@@ -4,5 +4,6 @@ use serde::{Serialize, Deserialize};
 
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct ParticleLifetime {
-    pub lifetime_ms: f32
+    pub lifetime_ms: f32,
+    pub delay_ms: f32
 }
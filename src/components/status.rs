@@ -0,0 +1,71 @@
+use std::ops::Deref;
+
+use specs::prelude::*;
+use specs::storage::MaskedStorage;
+use specs_derive::*;
+use serde::Deserialize;
+
+#[derive(PartialEq, Copy, Clone, Debug, Deserialize)]
+pub enum Condition {
+    Poisoned, Burnt, Frozen, Cursed, Confused, Afraid
+}
+
+impl Condition {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Condition::Poisoned => "poisoned",
+            Condition::Burnt => "burnt",
+            Condition::Frozen => "frozen",
+            Condition::Cursed => "cursed",
+            Condition::Confused => "confused",
+            Condition::Afraid => "afraid"
+        }
+    }
+}
+
+// Every condition on an entity is an entity of its own, pointing at whoever suffers it
+#[derive(Component, Debug, Clone)]
+pub struct StatusEffect{
+    pub target: Entity,
+    pub condition: Condition,
+    pub turns: i32,
+    pub potency: i32
+}
+
+impl StatusEffect {
+    pub fn apply(entities: &Entities, store: &mut WriteStorage<StatusEffect>, target: Entity, condition: Condition, turns: i32, potency: i32) {
+        let existing = (entities, &*store).join()
+            .find(|(_, effect)| effect.target == target && effect.condition == condition)
+            .map(|(e, _)| e);
+
+        match existing.and_then(|e| store.get_mut(e)) {
+            None => {
+                let effect = entities.create();
+                store.insert(effect, StatusEffect{ target, condition, turns, potency }).expect("Unable to insert StatusEffect");
+            }
+            // Poison piles up, a curse lingers longer, anything else just refreshes
+            Some(effect) => match condition {
+                Condition::Poisoned => {
+                    effect.potency += potency;
+                    effect.turns = i32::max(effect.turns, turns);
+                }
+                Condition::Cursed => effect.turns += turns,
+                Condition::Burnt | Condition::Frozen | Condition::Confused | Condition::Afraid => {
+                    effect.potency = i32::max(effect.potency, potency);
+                    effect.turns = i32::max(effect.turns, turns);
+                }
+            }
+        }
+    }
+}
+
+pub fn has_condition<D: Deref<Target = MaskedStorage<StatusEffect>>>(target: Entity, condition: Condition, store: &Storage<StatusEffect, D>) -> bool {
+    store.join().any(|effect| effect.target == target && effect.condition == condition)
+}
+
+#[derive(Component, Debug, Clone, Deserialize)]
+pub struct InflictsCondition{
+    pub condition: Condition,
+    pub turns: i32,
+    pub potency: i32
+}
@@ -0,0 +1,41 @@
+use specs::prelude::*;
+use specs_derive::*;
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Upgrade {
+    Toughness, KeenEyes, SecondWind, CurseWard
+}
+
+pub const UPGRADES : [Upgrade; 4] = [Upgrade::Toughness, Upgrade::KeenEyes, Upgrade::SecondWind, Upgrade::CurseWard];
+
+impl Upgrade {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Upgrade::Toughness => "Toughness",
+            Upgrade::KeenEyes => "Keen Eyes",
+            Upgrade::SecondWind => "Second Wind",
+            Upgrade::CurseWard => "Curse Ward"
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Upgrade::Toughness => "+2 vigor",
+            Upgrade::KeenEyes => "+2 sight range",
+            Upgrade::SecondWind => "heal a quarter of your hp on each new level",
+            Upgrade::CurseWard => "curses wear off and take hold slower"
+        }
+    }
+}
+
+// Permanent upgrades drafted between levels, the same one can be taken more than once
+#[derive(Component, Debug, Default)]
+pub struct Upgrades {
+    pub taken: Vec<Upgrade>
+}
+
+impl Upgrades {
+    pub fn count(&self, upgrade: Upgrade) -> i32 {
+        self.taken.iter().filter(|taken| **taken == upgrade).count() as i32
+    }
+}
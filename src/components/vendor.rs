@@ -0,0 +1,14 @@
+use specs::prelude::*;
+use specs_derive::*;
+use serde::Deserialize;
+
+#[derive(PartialEq, Copy, Clone, Debug, Deserialize)]
+pub enum VendorKind {
+    Blacksmith, Merchant
+}
+
+// Walking into a vendor opens their menu instead of a fight
+#[derive(Component, Debug, Clone)]
+pub struct Vendor{
+    pub kind: VendorKind
+}
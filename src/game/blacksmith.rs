@@ -0,0 +1,122 @@
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+use crate::components::*;
+
+use super::GameLog;
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum BlacksmithService {
+    Enchant, Repair, RemoveCurse, Reforge
+}
+
+pub const BLACKSMITH_SERVICES : [BlacksmithService; 4] = [BlacksmithService::Enchant, BlacksmithService::Repair, BlacksmithService::RemoveCurse, BlacksmithService::Reforge];
+
+impl BlacksmithService {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BlacksmithService::Enchant => "Enchant",
+            BlacksmithService::Repair => "Repair",
+            BlacksmithService::RemoveCurse => "Remove curse",
+            BlacksmithService::Reforge => "Reforge"
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            BlacksmithService::Enchant => "+1 to an item's power or armor",
+            BlacksmithService::Repair => "mend worn or broken gear",
+            BlacksmithService::RemoveCurse => "break the hold of a cursed item",
+            BlacksmithService::Reforge => "re-roll an item's attribute bonus"
+        }
+    }
+}
+
+// None when the service makes no sense for that item
+pub fn service_price(ecs: &World, service: BlacksmithService, item: Entity) -> Option<i32> {
+    match service {
+        BlacksmithService::Enchant => {
+            // Enchantment only sharpens a blade or thickens armor, anything else would gain nothing
+            if ecs.read_storage::<MeleeWeapon>().get(item).is_none() && ecs.read_storage::<Wearable>().get(item).is_none() {
+                return None;
+            }
+            let level = ecs.read_storage::<Enchantment>().get(item).map_or(0, |e| e.level);
+            Some(25 * (level + 1))
+        }
+        BlacksmithService::Repair => {
+            let durability = ecs.read_storage::<Durability>();
+            durability.get(item)
+                .filter(|d| d.current < d.max)
+                .map(|d| i32::max(5, (d.max - d.current) * 2))
+        }
+        BlacksmithService::RemoveCurse => ecs.read_storage::<CursedItem>().get(item).map(|_| 40),
+        // A cursed item has to be freed before it can be reworked
+        BlacksmithService::Reforge => match ecs.read_storage::<CursedItem>().get(item) {
+            None => Some(30),
+            Some(_) => None
+        }
+    }
+}
+
+pub fn perform_service(ecs: &mut World, service: BlacksmithService, item: Entity) {
+    let price = match service_price(ecs, service, item) {
+        None => {
+            ecs.fetch_mut::<GameLog>().entries.push("The blacksmith shakes their head.".to_string());
+            return;
+        }
+        Some(price) => price
+    };
+
+    let player_entity = *ecs.fetch::<Entity>();
+    {
+        let mut pool_stats = ecs.write_storage::<PoolStats>();
+        let mut log = ecs.fetch_mut::<GameLog>();
+        match pool_stats.get_mut(player_entity) {
+            Some(stats) if stats.gold >= price => stats.gold -= price,
+            _ => {
+                log.entries.push(format!("You can't afford that, it costs {} gold.", price));
+                return;
+            }
+        }
+    }
+
+    let item_name = ecs.read_storage::<Name>().get(item).map_or("item".to_string(), |n| n.name.clone());
+    match service {
+        BlacksmithService::Enchant => {
+            let mut enchantments = ecs.write_storage::<Enchantment>();
+            match enchantments.get_mut(item) {
+                Some(enchantment) => enchantment.level += 1,
+                None => { enchantments.insert(item, Enchantment{ level: 1 }).expect("Unable to insert Enchantment"); }
+            }
+        }
+        BlacksmithService::Repair => {
+            if let Some(durability) = ecs.write_storage::<Durability>().get_mut(item) {
+                durability.current = durability.max;
+            }
+        }
+        BlacksmithService::RemoveCurse => {
+            ecs.write_storage::<CursedItem>().remove(item);
+        }
+        BlacksmithService::Reforge => {
+            let bonus = {
+                let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+                let amount = rng.roll_dice(1, 2);
+                match rng.roll_dice(1, 4) {
+                    1 => AttributeBonus{ might: amount, ..Default::default() },
+                    2 => AttributeBonus{ agility: amount, ..Default::default() },
+                    3 => AttributeBonus{ wits: amount, ..Default::default() },
+                    _ => AttributeBonus{ vigor: amount, ..Default::default() }
+                }
+            };
+            ecs.write_storage::<AttributeBonus>().insert(item, bonus).expect("Unable to insert AttributeBonus");
+        }
+    }
+
+    // Whoever wears it needs their stats worked out again
+    let owner = ecs.read_storage::<Equipped>().get(item).map(|e| e.owner);
+    if let Some(owner) = owner {
+        ecs.write_storage::<RecalculateStats>().insert(owner, RecalculateStats{}).expect("Unable to insert RecalculateStats");
+    }
+
+    ecs.fetch_mut::<GameLog>().entries.push(format!("The blacksmith works on your {}: {}, for {} gold.", item_name, service.name().to_lowercase(), price));
+}
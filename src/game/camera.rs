@@ -1,7 +1,7 @@
 use specs::prelude::*;
 use bracket_lib::prelude::*;
 
-use crate::{map::{Map, TileType}, components::{Position, Renderable}};
+use crate::{map::{Map, TileType}, components::{Position, Renderable, ParticleLifetime}};
 
 pub fn get_screen_bounds(ecs: &World, ctx : &mut BTerm) -> (i32, i32, i32, i32) {
     let player_pos = ecs.fetch::<Point>();
@@ -50,12 +50,16 @@ pub fn render_camera(ecs: &World, ctx : &mut BTerm) {
     // Render entities
     let positions = ecs.read_storage::<Position>();
     let renderables = ecs.read_storage::<Renderable>();
+    let particles = ecs.read_storage::<ParticleLifetime>();
     // let hidden = ecs.read_storage::<Hidden>();
     let map = ecs.fetch::<Map>();
 
-    let mut data = (&positions, &renderables).join().collect::<Vec<_>>();
+    // Particles still waiting on their delay are not shown yet
+    let mut data = (&positions, &renderables, particles.maybe()).join()
+        .filter(|(_, _, particle)| particle.is_none_or(|p| p.delay_ms <= 0.0))
+        .collect::<Vec<_>>();
     data.sort_by(|&a, &b| b.1.render_order.cmp(&a.1.render_order) );
-    for (pos, render) in data.iter() {
+    for (pos, render, _) in data.iter() {
         let idx = map.xy_idx(pos.x, pos.y);
         if map.visible_tiles[idx] {
             let entity_screen_x = pos.x - min_x;
@@ -124,6 +128,11 @@ fn get_tile_glyph(idx: usize, map : &Map) -> (FontCharType, RGB, RGB) {
             fg = RGB::from_f32(0., 1.0, 1.0);
             bg = RGB::from_f32(0., 0., 0.);
         }
+        TileType::Grass => {
+            glyph = to_cp437('"');
+            fg = RGB::from_f32(0.4, 0.6, 0.1);
+            bg = RGB::from_f32(0., 0., 0.);
+        }
 
     }
     // if map.bloodstains.contains(&idx) { bg = RGB::from_f32(0.75, 0., 0.); }
@@ -2,21 +2,37 @@ use bracket_lib::prelude::*;
 use specs::prelude::*;
 use crate::components::*;
 
-use super::GameLog;
+use super::{GameLog, FactionTable, PLAYER_FACTION};
 
 pub fn try_curse(ecs: &mut World) {
+    let (cursed, wards, cursed_items) = {
+        let active_entity = ecs.fetch::<ActiveEntity>();
+        let player_entity = ecs.fetch::<Entity>();
+        let status_effects = ecs.read_storage::<StatusEffect>();
+        let upgrades = ecs.read_storage::<Upgrades>();
+        let equipped = ecs.read_storage::<Equipped>();
+        let cursed_items = ecs.read_storage::<CursedItem>();
+        // The curse counts whether it sits on the hero or on the body we are riding
+        (has_condition(*player_entity, Condition::Cursed, &status_effects) || has_condition(active_entity.target, Condition::Cursed, &status_effects),
+         upgrades.get(*player_entity).map_or(0, |u| u.count(Upgrade::CurseWard)),
+         (&equipped, &cursed_items).join().filter(|(e, _)| e.owner == *player_entity).count() as i32)
+    };
+
     let mut rng = ecs.fetch_mut::<RandomNumberGenerator>();
-    let roll = rng.roll_dice(1, 4);
+    // Being cursed doubles the odds of a shift, so does every cursed item worn, every ward makes it rarer
+    let roll = rng.roll_dice(1, i32::max(2, (if cursed { 2 } else { 4 }) + wards * 2 - cursed_items));
 
     // Weighted probabily
     // Different maps with different weights?
     // Harder more shifts?
     match roll {
-        3 => {
+        1 => {
             // get resources/components
             let controllables = ecs.write_storage::<Controllable>();
             let positions = ecs.read_storage::<Position>();
             let names = ecs.read_storage::<Name>();
+            let factions = ecs.read_storage::<Faction>();
+            let faction_table = ecs.fetch::<FactionTable>();
             let mut viewsheds = ecs.write_storage::<Viewshed>();
             let mut active_target = ecs.fetch_mut::<ActiveEntity>();
             let entities = ecs.entities();
@@ -24,14 +40,30 @@ pub fn try_curse(ecs: &mut World) {
         
             // group into a vec and turn into a slice (bc im not that smart)
             // pick one randomly
-            let data = (&entities, &controllables, &names, &positions).join().collect::<Vec<_>>();
-            let random = bracket_lib::random::RandomNumberGenerator::random_slice_entry(&mut rng, data.as_slice()).unwrap();
+            let data = (&entities, &controllables, &names, &positions).join()
+                .filter(|(entity, _, _, _)| *entity != active_target.target)
+                .collect::<Vec<_>>();
+            let random = match bracket_lib::random::RandomNumberGenerator::random_slice_entry(&mut rng, data.as_slice()) {
+                None => return,
+                Some(random) => random
+            };
             active_target.target = random.0;
         
             let viewshed = viewsheds.get_mut(active_target.target).unwrap();
             viewshed.dirty = true;
+
+            // The camera follows whoever we are now
+            let mut camera = ecs.write_resource::<Point>();
+            *camera = Point::new(random.3.x, random.3.y);
         
             log.entries.push(format!("You've been cursed! You are now the {}", random.2.name));
+
+            // Mobs see whoever we control as the player, so the body's kin may turn on it
+            if let Some(faction) = factions.get(active_target.target) {
+                if faction.name != PLAYER_FACTION && faction_table.reaction(&faction.name, PLAYER_FACTION) == Reaction::Attack {
+                    log.entries.push(format!("The {} sense the curse and turn on you!", faction.name));
+                }
+            }
         }
         _ => {}
     }
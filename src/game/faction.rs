@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+
+use specs::prelude::*;
+use crate::components::*;
+
+pub const PLAYER_FACTION : &str = "Player";
+
+// Reaction table system
+#[derive(Default)]
+pub struct FactionTable {
+    reactions: HashMap<String, HashMap<String, Reaction>>
+}
+
+impl FactionTable {
+    pub fn new() -> FactionTable {
+        FactionTable{ reactions: HashMap::new() }
+    }
+
+    pub fn add<S:ToString>(mut self, my_faction: S, their_faction: S, reaction: Reaction) -> FactionTable {
+        self.reactions
+            .entry(my_faction.to_string())
+            .or_default()
+            .insert(their_faction.to_string(), reaction);
+        self
+    }
+
+    // Same faction or nothing on the table means we leave each other alone
+    pub fn reaction(&self, my_faction: &str, their_faction: &str) -> Reaction {
+        if my_faction == their_faction { return Reaction::Ignore; }
+
+        match self.reactions.get(my_faction) {
+            None => Reaction::Ignore,
+            Some(table) => *table.get(their_faction).unwrap_or(&Reaction::Ignore)
+        }
+    }
+}
+
+pub fn faction_table() -> FactionTable {
+    FactionTable::new()
+        .add(PLAYER_FACTION, "Goblins", Reaction::Attack)
+        .add(PLAYER_FACTION, "Orcs", Reaction::Attack)
+        .add(PLAYER_FACTION, "Slimes", Reaction::Attack)
+        .add(PLAYER_FACTION, "Dragons", Reaction::Attack)
+        .add("Goblins", PLAYER_FACTION, Reaction::Attack)
+        .add("Goblins", "Orcs", Reaction::Flee)
+        .add("Orcs", PLAYER_FACTION, Reaction::Attack)
+        .add("Orcs", "Goblins", Reaction::Ignore)
+        .add("Slimes", PLAYER_FACTION, Reaction::Attack)
+        .add("Dragons", PLAYER_FACTION, Reaction::Attack)
+        .add("Goblins", "Dragons", Reaction::Flee)
+        .add("Orcs", "Dragons", Reaction::Flee)
+}
+
+// Whoever the curse put us in is seen as the player, so that body's former allies turn on it
+pub fn effective_faction<'a>(entity: Entity, active_entity: &ActiveEntity, factions: &'a ReadStorage<Faction>) -> Option<&'a str> {
+    if entity == active_entity.target {
+        return Some(PLAYER_FACTION);
+    }
+    factions.get(entity).map(|f| f.name.as_str())
+}
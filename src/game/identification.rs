@@ -0,0 +1,80 @@
+use std::collections::{HashMap, HashSet};
+
+use bracket_lib::random::RandomNumberGenerator;
+use specs::prelude::*;
+use serde::Deserialize;
+
+use crate::components::*;
+
+pub const POTION_LOOKS : [&str; 8] = ["Murky", "Bubbling", "Glowing", "Smoky", "Viscous", "Fizzing", "Cloudy", "Oily"];
+const SCROLL_SYLLABLES : [&str; 12] = ["ZOT", "HRA", "KEL", "VOR", "IX", "NAB", "ULM", "QAR", "ETH", "DRO", "MAK", "SIL"];
+
+// What a disguised item passes itself off as
+#[derive(PartialEq, Copy, Clone, Debug, Deserialize)]
+pub enum Disguise {
+    Potion, Scroll
+}
+
+// What every potion and scroll looks like this run, and which of them the hero has worked out
+pub struct Identification {
+    appearances: HashMap<String, String>,
+    known: HashSet<String>
+}
+
+impl Identification {
+    // Potions and scrolls keep their true names hidden until used or identified
+    pub fn new(rng: &mut RandomNumberGenerator, disguised: &[(String, Disguise)]) -> Identification {
+        let mut looks : Vec<&str> = POTION_LOOKS.to_vec();
+        let mut appearances : HashMap<String, String> = HashMap::new();
+
+        for (name, disguise) in disguised.iter() {
+            let appearance = if *disguise == Disguise::Potion {
+                let look = looks.remove(rng.range(0, looks.len() as i32) as usize);
+                format!("{} Potion", look)
+            } else {
+                // Gibberish titles, rerolled until no two scrolls share one
+                loop {
+                    let syllables = rng.range(2, 4);
+                    let title : String = (0 .. syllables).map(|_| SCROLL_SYLLABLES[rng.range(0, SCROLL_SYLLABLES.len() as i32) as usize]).collect();
+                    let appearance = format!("Scroll of {}", title);
+                    if !appearances.values().any(|a| *a == appearance) {
+                        break appearance;
+                    }
+                }
+            };
+            appearances.insert(name.clone(), appearance);
+        }
+
+        Identification{ appearances, known: HashSet::new() }
+    }
+
+    // The disguise an item goes by, None once it is known or if it never had one
+    pub fn appearance(&self, name: &str) -> Option<&str> {
+        if self.known.contains(name) { return None; }
+        self.appearances.get(name).map(|a| a.as_str())
+    }
+
+    pub fn is_known(&self, name: &str) -> bool {
+        self.known.contains(name)
+    }
+
+    // Returns true the first time an item is worked out
+    pub fn identify(&mut self, name: &str) -> bool {
+        self.known.insert(name.to_string())
+    }
+}
+
+// Disguises a freshly spawned potion or scroll the hero has not identified yet
+pub fn obfuscate(ecs: &mut World, item: Entity) {
+    let real_name = match ecs.read_storage::<Name>().get(item) {
+        None => return,
+        Some(name) => name.name.clone()
+    };
+    let appearance = match ecs.fetch::<Identification>().appearance(&real_name) {
+        None => return,
+        Some(appearance) => appearance.to_string()
+    };
+
+    ecs.write_storage::<Name>().insert(item, Name{ name: appearance }).expect("Unable to insert Name");
+    ecs.write_storage::<Unidentified>().insert(item, Unidentified{ name: real_name }).expect("Unable to insert Unidentified");
+}
@@ -0,0 +1,165 @@
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+use crate::{components::*, RunState};
+
+use super::{GameLog, spawn_named_entity};
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum ItemAction {
+    Use, Throw, Drop, Equip, Inspect
+}
+
+pub const ITEM_ACTIONS : [ItemAction; 5] = [ItemAction::Use, ItemAction::Throw, ItemAction::Drop, ItemAction::Equip, ItemAction::Inspect];
+
+pub const THROW_RANGE : i32 = 6;
+
+impl ItemAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ItemAction::Use => "Use",
+            ItemAction::Throw => "Throw",
+            ItemAction::Drop => "Drop",
+            ItemAction::Equip => "Equip",
+            ItemAction::Inspect => "Inspect"
+        }
+    }
+}
+
+pub fn item_action(ecs: &mut World, item: Entity, action: ItemAction) -> RunState {
+    match action {
+        ItemAction::Use => use_item(ecs, item),
+        ItemAction::Throw => {
+            // Potions show where they will splash
+            let radius = if ecs.read_storage::<Shatters>().get(item).is_some() { 1 } else { 0 };
+            let cursor = *ecs.fetch::<Point>();
+            RunState::ShowTargeting{ range: THROW_RANGE, radius, item: Some(item), cursor, thrown: true }
+        }
+        ItemAction::Drop => {
+            let item = split_stack(ecs, item);
+            let active_entity = ecs.fetch::<ActiveEntity>();
+            let mut intent = ecs.write_storage::<DropItemIntent>();
+            intent.insert(active_entity.target, DropItemIntent{ item }).expect("Unable to insert intent");
+            RunState::PlayerTurn
+        }
+        ItemAction::Equip => {
+            if ecs.read_storage::<Equippable>().get(item).is_none() {
+                log_cannot(ecs, item, "equip");
+                return RunState::AwaitingInput;
+            }
+            let active_entity = ecs.fetch::<ActiveEntity>();
+            let mut intent = ecs.write_storage::<EquipItemIntent>();
+            intent.insert(active_entity.target, EquipItemIntent{ item }).expect("Unable to insert intent");
+            RunState::PlayerTurn
+        }
+        ItemAction::Inspect => RunState::InspectItem{ item }
+    }
+}
+
+// Anything aimed goes through targeting first, the rest is used on the spot
+fn use_item(ecs: &mut World, item: Entity) -> RunState {
+    // Bows and wands are shot with fire, never turned on ourselves
+    if ecs.read_storage::<RangedWeapon>().get(item).is_some() {
+        let names = ecs.read_storage::<Name>();
+        let mut log = ecs.fetch_mut::<GameLog>();
+        log.entries.push(format!("The {} is fired at a target, not used.", names.get(item).map_or("item", |n| n.name.as_str())));
+        return RunState::AwaitingInput;
+    }
+
+    // A condition on its own only makes sense aimed at someone else
+    let aimed = ecs.read_storage::<Ranged>().get(item).is_some();
+    let usable = ecs.read_storage::<Heals>().get(item).is_some()
+        || ecs.read_storage::<InflictsDamage>().get(item).is_some()
+        || (aimed && ecs.read_storage::<InflictsCondition>().get(item).is_some())
+        || ecs.read_storage::<RemovesCurse>().get(item).is_some()
+        || ecs.read_storage::<Identifies>().get(item).is_some()
+        || ecs.read_storage::<MagicMapper>().get(item).is_some()
+        || ecs.read_storage::<TeleportSelf>().get(item).is_some()
+        || ecs.read_storage::<ProvidesFood>().get(item).is_some();
+    if !usable {
+        log_cannot(ecs, item, "use");
+        return RunState::AwaitingInput;
+    }
+
+    if let Some(ranged) = ecs.read_storage::<Ranged>().get(item) {
+        let radius = ecs.read_storage::<AreaOfEffect>().get(item).map_or(0, |aoe| aoe.radius);
+        let cursor = *ecs.fetch::<Point>();
+        return RunState::ShowTargeting{ range: ranged.range, radius, item: Some(item), cursor, thrown: false };
+    }
+
+    let active_entity = ecs.fetch::<ActiveEntity>();
+    let mut intent = ecs.write_storage::<UseItemIntent>();
+    intent.insert(active_entity.target, UseItemIntent{ item, target: None }).expect("Unable to insert intent");
+    RunState::PlayerTurn
+}
+
+fn log_cannot(ecs: &World, item: Entity, verb: &str) {
+    let names = ecs.read_storage::<Name>();
+    let mut log = ecs.fetch_mut::<GameLog>();
+    log.entries.push(format!("You can't {} the {}.", verb, names.get(item).map_or("item", |n| n.name.as_str())));
+}
+
+// Worn gear comes off first, unless a curse holds it; a stack only parts with one unit
+pub fn throw_item(ecs: &mut World, item: Entity, target: Point) -> bool {
+    let thrower = ecs.fetch::<ActiveEntity>().target;
+    if ecs.read_storage::<Equipped>().get(item).is_some() {
+        if ecs.read_storage::<CursedItem>().get(item).is_some() {
+            let names = ecs.read_storage::<Name>();
+            ecs.fetch_mut::<GameLog>().entries.push(format!("The {} is cursed, it will not come off!", names.get(item).map_or("item", |n| n.name.as_str())));
+            return false;
+        }
+        ecs.write_storage::<Equipped>().remove(item);
+        ecs.write_storage::<RecalculateStats>().insert(thrower, RecalculateStats{}).expect("Unable to insert RecalculateStats");
+    }
+
+    let item = split_stack(ecs, item);
+    ecs.write_storage::<ThrowItemIntent>().insert(thrower, ThrowItemIntent{ item, target }).expect("Unable to insert intent");
+    true
+}
+
+// Hands an item over, folding it into a matching stack the new owner already holds
+pub fn give_item(ecs: &mut World, item: Entity, owner: Entity) {
+    let entities = ecs.entities();
+    let names = ecs.read_storage::<Name>();
+    let mut items_owned = ecs.write_storage::<ItemOwned>();
+    let mut stacks = ecs.write_storage::<Stackable>();
+    ecs.write_storage::<Position>().remove(item);
+
+    if let Some(stack) = matching_stack(&entities, item, owner, &items_owned, &names, &stacks) {
+        let quantity = stacks.get(item).map_or(1, |s| s.quantity);
+        if let Some(stack) = stacks.get_mut(stack) {
+            stack.quantity += quantity;
+        }
+        entities.delete(item).expect("Unable to delete item");
+    } else {
+        items_owned.insert(item, ItemOwned{ owner }).expect("Unable to insert ItemOwned");
+    }
+}
+
+// Takes a single unit off a stack, left with the same owner; anything else is already a single unit
+pub fn split_stack(ecs: &mut World, item: Entity) -> Entity {
+    let quantity = ecs.read_storage::<Stackable>().get(item).map_or(1, |s| s.quantity);
+    if quantity < 2 {
+        return item;
+    }
+    // A disguised unit is spawned from its real name and disguised again
+    let name = match (ecs.read_storage::<Unidentified>().get(item), ecs.read_storage::<Name>().get(item)) {
+        (Some(mystery), _) => mystery.name.clone(),
+        (None, Some(name)) => name.name.clone(),
+        (None, None) => return item
+    };
+    let owner = ecs.read_storage::<ItemOwned>().get(item).map(|owned| owned.owner);
+
+    let unit = match spawn_named_entity(ecs, &name, 0, 0) {
+        None => return item,
+        Some(unit) => unit
+    };
+    ecs.write_storage::<Position>().remove(unit);
+    if let Some(owner) = owner {
+        ecs.write_storage::<ItemOwned>().insert(unit, ItemOwned{ owner }).expect("Unable to insert ItemOwned");
+    }
+    if let Some(stack) = ecs.write_storage::<Stackable>().get_mut(item) {
+        stack.quantity -= 1;
+    }
+    unit
+}
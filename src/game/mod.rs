@@ -4,9 +4,23 @@ mod curse;
 mod spawner;
 mod log;
 mod camera;
+mod faction;
+mod progression;
+mod upgrades;
+mod blacksmith;
+mod shop;
+mod inventory;
+mod identification;
 
 pub use player::*;
 pub use curse::*;
 pub use spawner::*;
 pub use log::*;
-pub use camera::*;
\ No newline at end of file
+pub use camera::*;
+pub use faction::*;
+pub use progression::*;
+pub use upgrades::*;
+pub use blacksmith::*;
+pub use shop::*;
+pub use inventory::*;
+pub use identification::*;
\ No newline at end of file
@@ -1,18 +1,26 @@
 use bracket_lib::terminal::{BTerm, VirtualKeyCode, Point};
+use bracket_lib::geometry::DistanceAlg;
+use bracket_lib::random::RandomNumberGenerator;
 use specs::prelude::*;
 use crate::{components::*, RunState};
 use crate::map::{Map, TileType};
+use crate::systems::ranged_weapon;
 
-use super::{GameLog};
+use super::{GameLog, FactionTable, PLAYER_FACTION, ShopMode, effective_faction};
 use super::super::State;
 
 use std::cmp::{min, max};
 
-pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
+pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) -> RunState {
+    let (delta_x, delta_y) = stumble(ecs, delta_x, delta_y);
     let mut positions = ecs.write_storage::<Position>();
     let mut melee_intent = ecs.write_storage::<MeleeIntent>();
     let mut viewsheds = ecs.write_storage::<Viewshed>();
     let stats = ecs.read_storage::<CombatStats>();
+    let factions = ecs.read_storage::<Faction>();
+    let faction_table = ecs.fetch::<FactionTable>();
+    let vendors = ecs.read_storage::<Vendor>();
+    let status_effects = ecs.read_storage::<StatusEffect>();
 
     // let controllables = ecs.read_storage::<Controllable>();
     let map = ecs.fetch::<Map>();
@@ -25,10 +33,23 @@ pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
     let viewshed = viewsheds.get_mut(active_entity.target).unwrap();
     let dest_idx = map.xy_idx(active_target_pos.x + delta_x, active_target_pos.y + delta_y);
     for potential_target in map.tile_content[dest_idx].iter() {
+        if let Some(vendor) = vendors.get(*potential_target) {
+            match vendor.kind {
+                VendorKind::Blacksmith => return RunState::ShowBlacksmith{ service: None },
+                VendorKind::Merchant => return RunState::ShowShop{ vendor: *potential_target, mode: ShopMode::Buy }
+            }
+        }
+
         let target = stats.get(*potential_target);
-        if let Some(_t) = target {
+        let their_faction = effective_faction(*potential_target, &active_entity, &factions);
+        if let (Some(_t), Some(their_faction)) = (target, their_faction) {
+            if faction_table.reaction(PLAYER_FACTION, their_faction) != Reaction::Attack { continue; }
+            if has_condition(active_entity.target, Condition::Afraid, &status_effects) {
+                ecs.fetch_mut::<GameLog>().entries.push("You are too afraid to attack!".to_string());
+                return RunState::AwaitingInput;
+            }
             melee_intent.insert(active_entity.target, MeleeIntent { target: *potential_target }).expect("Unable to insert melee intent");
-            return;
+            return RunState::PlayerTurn;
         }
     }
 
@@ -43,6 +64,7 @@ pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
 
         entity_moved.insert(active_entity.target, EntityMoved {}).expect("Unable to insert EntityMoved marker");
     }
+    RunState::PlayerTurn
 }
 
 fn pickup_item(ecs: &mut World) {
@@ -69,6 +91,65 @@ fn pickup_item(ecs: &mut World) {
     }
 }
 
+// Aim with whatever we can shoot, starting on the closest hostile in reach
+fn start_firing(ecs: &mut World) -> Option<RunState> {
+    let active_entity = ecs.fetch::<ActiveEntity>();
+    let entities = ecs.entities();
+    let ranged_weapons = ecs.read_storage::<RangedWeapon>();
+    let items_owned = ecs.read_storage::<ItemOwned>();
+    let positions = ecs.read_storage::<Position>();
+    let pool_stats = ecs.read_storage::<PoolStats>();
+    let factions = ecs.read_storage::<Faction>();
+    let faction_table = ecs.fetch::<FactionTable>();
+    let map = ecs.fetch::<Map>();
+    let mut log = ecs.fetch_mut::<GameLog>();
+
+    let weapon = match ranged_weapon(active_entity.target, &entities, &ranged_weapons, &items_owned) {
+        None => {
+            log.entries.push("You have nothing to shoot with.".to_string());
+            return None;
+        }
+        Some((_, weapon)) => weapon
+    };
+
+    let shooter_pos = positions.get(active_entity.target).unwrap();
+    let shooter_point = Point::new(shooter_pos.x, shooter_pos.y);
+    let mut closest : Option<(Point, f32)> = None;
+    for (entity, pos, _stats) in (&entities, &positions, &pool_stats).join() {
+        if entity == active_entity.target || !map.visible_tiles[map.xy_idx(pos.x, pos.y)] { continue; }
+        let hostile = effective_faction(entity, &active_entity, &factions)
+            .is_some_and(|their_faction| faction_table.reaction(PLAYER_FACTION, their_faction) == Reaction::Attack);
+        let distance = DistanceAlg::Pythagoras.distance2d(shooter_point, Point::new(pos.x, pos.y));
+        if hostile && distance <= weapon.range as f32 && closest.is_none_or(|(_, d)| distance < d) {
+            closest = Some((Point::new(pos.x, pos.y), distance));
+        }
+    }
+
+    let cursor = closest.map_or(shooter_point, |(point, _)| point);
+    Some(RunState::ShowTargeting{ range: weapon.range, radius: 0, item: None, cursor, thrown: false })
+}
+
+pub fn fire_at(ecs: &mut World, target: Point) -> bool {
+    let active_entity = ecs.fetch::<ActiveEntity>();
+    let map = ecs.fetch::<Map>();
+    let pool_stats = ecs.read_storage::<PoolStats>();
+
+    let idx = map.xy_idx(target.x, target.y);
+    let victim = map.tile_content[idx].iter().find(|e| **e != active_entity.target && pool_stats.get(**e).is_some());
+    match victim {
+        None => {
+            let mut log = ecs.fetch_mut::<GameLog>();
+            log.entries.push("There is nothing there to shoot at.".to_string());
+            false
+        }
+        Some(victim) => {
+            let mut ranged_intent = ecs.write_storage::<RangedIntent>();
+            ranged_intent.insert(active_entity.target, RangedIntent{ target: *victim }).expect("Unable to insert ranged intent");
+            true
+        }
+    }
+}
+
 pub fn try_next_level(ecs: &mut World) -> bool {
     let active_entity = ecs.fetch::<ActiveEntity>();
     let positions = ecs.read_storage::<Position>();
@@ -85,16 +166,54 @@ pub fn try_next_level(ecs: &mut World) -> bool {
     }
 }
 
+// A confused body lurches off in a random direction half the time
+fn stumble(ecs: &mut World, delta_x: i32, delta_y: i32) -> (i32, i32) {
+    let active_entity = ecs.fetch::<ActiveEntity>();
+    let status_effects = ecs.read_storage::<StatusEffect>();
+    if !has_condition(active_entity.target, Condition::Confused, &status_effects) {
+        return (delta_x, delta_y);
+    }
+
+    let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+    if rng.roll_dice(1, 2) == 1 {
+        return (delta_x, delta_y);
+    }
+    ecs.fetch_mut::<GameLog>().entries.push("You stumble about in confusion.".to_string());
+    (rng.range(-1, 2), rng.range(-1, 2))
+}
+
+// Frozen bodies sit the turn out
+fn is_frozen(ecs: &World) -> bool {
+    let active_entity = ecs.fetch::<ActiveEntity>();
+    let status_effects = ecs.read_storage::<StatusEffect>();
+    if has_condition(active_entity.target, Condition::Frozen, &status_effects) {
+        let mut log = ecs.fetch_mut::<GameLog>();
+        log.entries.push("You are frozen solid!".to_string());
+        return true;
+    }
+    false
+}
+
 pub fn player_input(gs: &mut State, ctx: &mut BTerm) -> RunState {
+    if is_frozen(&gs.ecs) {
+        return RunState::PlayerTurn;
+    }
+
     // Player movement
     match ctx.key {
         None => { return RunState::AwaitingInput } // Nothing happened
         Some(key) => match key {
-            VirtualKeyCode::Left => try_move_player(-1, 0, &mut gs.ecs),
-            VirtualKeyCode::Right => try_move_player(1, 0, &mut gs.ecs),
-            VirtualKeyCode::Up => try_move_player(0, -1, &mut gs.ecs),
-            VirtualKeyCode::Down => try_move_player(0, 1, &mut gs.ecs),
+            VirtualKeyCode::Left => return try_move_player(-1, 0, &mut gs.ecs),
+            VirtualKeyCode::Right => return try_move_player(1, 0, &mut gs.ecs),
+            VirtualKeyCode::Up => return try_move_player(0, -1, &mut gs.ecs),
+            VirtualKeyCode::Down => return try_move_player(0, 1, &mut gs.ecs),
             VirtualKeyCode::G => pickup_item(&mut gs.ecs),
+            VirtualKeyCode::C => return RunState::CharacterSheet,
+            VirtualKeyCode::E => return RunState::ShowEquipment,
+            VirtualKeyCode::I => return RunState::ShowInventory,
+            VirtualKeyCode::F => {
+                return start_firing(&mut gs.ecs).unwrap_or(RunState::AwaitingInput);
+            },
             VirtualKeyCode::Period => {
                 if try_next_level(&mut gs.ecs) {
                     return RunState::NextLevel;
@@ -0,0 +1,62 @@
+use specs::prelude::*;
+
+use crate::components::*;
+
+use super::GameLog;
+
+// Total xp needed to reach a level: 20, 80, 180, 320...
+pub fn xp_for_level(level: i32) -> i32 {
+    (level - 1) * (level - 1) * 20
+}
+
+pub fn max_hp(vigor: i32, level: i32) -> i32 {
+    vigor * 2 + level * 5
+}
+
+pub fn attack(might: i32) -> i32 {
+    might
+}
+
+pub fn evade(agility: i32) -> i32 {
+    agility / 4
+}
+
+pub fn perception(wits: i32) -> i32 {
+    wits / 4
+}
+
+pub fn stealth(wits: i32) -> i32 {
+    wits / 4
+}
+
+// How far, how seasoned and how rich the hero got
+pub fn score(stats: &PoolStats, depth: i32) -> i32 {
+    depth * 100 + stats.xp + stats.gold
+}
+
+pub fn can_level_up(ecs: &World) -> bool {
+    let player_entity = ecs.fetch::<Entity>();
+    let pool_stats = ecs.read_storage::<PoolStats>();
+    pool_stats.get(*player_entity).is_some_and(|stats| stats.xp >= xp_for_level(stats.level + 1))
+}
+
+pub fn level_up(ecs: &mut World, raised: Attribute) {
+    let player_entity = ecs.fetch::<Entity>();
+    let mut attributes = ecs.write_storage::<Attributes>();
+    let mut pool_stats = ecs.write_storage::<PoolStats>();
+    let mut recalculate = ecs.write_storage::<RecalculateStats>();
+    let mut log = ecs.fetch_mut::<GameLog>();
+
+    if let (Some(attributes), Some(pool)) = (attributes.get_mut(*player_entity), pool_stats.get_mut(*player_entity)) {
+        attributes.get_mut(raised).base += 1;
+        pool.level += 1;
+
+        // The new max hp comes with the hp to fill it
+        let new_max = max_hp(attributes.vigor.value(), pool.level);
+        pool.hp.current += new_max - pool.hp.max;
+        pool.hp.max = new_max;
+        recalculate.insert(*player_entity, RecalculateStats{}).expect("Unable to insert RecalculateStats");
+
+        log.entries.push(format!("You reach level {}, your {} grows!", pool.level, raised.name()));
+    }
+}
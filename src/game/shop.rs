@@ -0,0 +1,85 @@
+use specs::prelude::*;
+
+use crate::components::*;
+
+use super::{GameLog, give_item, split_stack};
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum ShopMode {
+    Buy, Sell
+}
+
+// Base value plus what the blacksmith put into it, None if nobody would trade it
+pub fn item_price(ecs: &World, item: Entity) -> Option<i32> {
+    let values = ecs.read_storage::<Value>();
+    let enchantments = ecs.read_storage::<Enchantment>();
+    values.get(item).map(|value| value.gold + 25 * enchantments.get(item).map_or(0, |e| e.level))
+}
+
+// Merchants buy at half what they sell for
+pub fn sell_price(ecs: &World, item: Entity) -> Option<i32> {
+    item_price(ecs, item).map(|price| price / 2)
+}
+
+pub fn buy_item(ecs: &mut World, item: Entity) {
+    let price = match item_price(ecs, item) {
+        None => return,
+        Some(price) => price
+    };
+    let player_entity = *ecs.fetch::<Entity>();
+    let buyer = ecs.fetch::<ActiveEntity>().target;
+
+    {
+        let mut pool_stats = ecs.write_storage::<PoolStats>();
+        let names = ecs.read_storage::<Name>();
+        let mut log = ecs.fetch_mut::<GameLog>();
+        let item_name = names.get(item).map_or("item", |n| n.name.as_str());
+
+        match pool_stats.get_mut(player_entity) {
+            Some(stats) if stats.gold >= price => {
+                stats.gold -= price;
+                log.entries.push(format!("You buy the {} for {} gold.", item_name, price));
+            }
+            _ => {
+                log.entries.push(format!("You can't afford the {}, it costs {} gold.", item_name, price));
+                return;
+            }
+        }
+    }
+
+    // Stacks change hands one unit at a time
+    let item = split_stack(ecs, item);
+    give_item(ecs, item, buyer);
+}
+
+pub fn sell_item(ecs: &mut World, vendor: Entity, item: Entity) {
+    let price = match sell_price(ecs, item) {
+        None => return,
+        Some(price) => price
+    };
+    let player_entity = *ecs.fetch::<Entity>();
+
+    {
+        let mut pool_stats = ecs.write_storage::<PoolStats>();
+        let equipped = ecs.read_storage::<Equipped>();
+        let names = ecs.read_storage::<Name>();
+        let mut log = ecs.fetch_mut::<GameLog>();
+        let item_name = names.get(item).map_or("item", |n| n.name.as_str());
+
+        if equipped.get(item).is_some() {
+            log.entries.push(format!("You have to take off the {} first.", item_name));
+            return;
+        }
+
+        match pool_stats.get_mut(player_entity) {
+            None => return,
+            Some(stats) => {
+                stats.gold += price;
+                log.entries.push(format!("You sell the {} for {} gold.", item_name, price));
+            }
+        }
+    }
+
+    let item = split_stack(ecs, item);
+    give_item(ecs, item, vendor);
+}
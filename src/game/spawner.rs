@@ -1,5 +1,6 @@
 
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 
 use bracket_lib::random::RandomNumberGenerator;
 use bracket_lib::terminal::*;
@@ -7,16 +8,25 @@ use specs::prelude::*;
 
 use crate::map::{RoomRect, MAPWIDTH};
 use crate::components::*;
+use crate::raws::*;
+
+use super::{PLAYER_FACTION, max_hp, attack, evade, perception, stealth, give_item, obfuscate};
 
 const MAX_MONSTERS : i32 = 4;
+pub const NOGRAD_DEPTH : i32 = 10;
 
 pub fn player(ecs: &mut World, x: i32, y:i32) -> Entity {
+    let attributes = Attributes::new(10, 8, 12, 10);
+    let max_hp = max_hp(attributes.vigor.value(), 1);
+
     ecs
         .create_entity()
         .with(Player{})
         .with(Controllable{ current: true})
         .with(Position{ x, y})
         .with(Name{name: "Player".to_string() })
+        .with(Faction{ name: PLAYER_FACTION.to_string() })
+        .with(Stealth{ skill: stealth(attributes.wits.value()) })
         .with(BlocksTile{})
         .with(Viewshed{ visible_tiles : Vec::new(), range : 8, dirty: true })
         .with(Renderable{
@@ -26,23 +36,48 @@ pub fn player(ecs: &mut World, x: i32, y:i32) -> Entity {
             render_order: 0
         })
         .with(CombatStats{
-            attack: 10,
+            attack: attack(attributes.might.value()),
             defense: 10,
-            evade: 0
+            evade: evade(attributes.agility.value())
         })
         .with(PoolStats{
-            hp: SinglePoolStat { current: 25, max: 25 },
+            hp: SinglePoolStat { current: max_hp, max: max_hp },
             xp: 0,
             level: 1,
             gold: 0
         })
+        .with(attributes)
+        .with(Upgrades::default())
+        .with(HungerClock::new(HungerState::WellFed))
         .build()
 }
 
+// A merchant carries their stock like anyone carries a pack
+pub fn merchant(ecs: &mut World, x: i32, y: i32, depth: i32) {
+    let vendor = match spawn_named_entity(ecs, "Merchant", x, y) {
+        None => return,
+        Some(vendor) => vendor
+    };
+
+    let stock : Vec<String> = {
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        let table = spawn_table(ecs, SpawnTable::Shop, depth);
+        let count = rng.roll_dice(1, 3) + 3;
+        (0 .. count).map(|_| table.roll(&mut rng)).collect()
+    };
+    for name in stock.iter() {
+        if let Some(item) = spawn_named_entity(ecs, name, x, y) {
+            give_item(ecs, item, vendor);
+        }
+    }
+}
+
 // Spawning entities functions -> random and non-random
 pub fn spawn_room(ecs: &mut World, room: &RoomRect, depth: i32) {
-    let spawn_table = room_random_table(depth);
+    let room_table = spawn_table(ecs, SpawnTable::Rooms, depth);
+    let caps = ecs.fetch::<RawMaster>().room_caps();
     let mut spawn_points : HashMap<usize, String> = HashMap::new();
+    let mut placed : HashMap<String, i32> = HashMap::new();
 
     {
         let mut rng = ecs.write_resource::<RandomNumberGenerator>();
@@ -55,8 +90,16 @@ pub fn spawn_room(ecs: &mut World, room: &RoomRect, depth: i32) {
                 let x = (room.x1 + rng.roll_dice(1, i32::abs(room.x2 - room.x1))) as usize;
                 let y = (room.y1 + rng.roll_dice(1, i32::abs(room.y2 - room.y1))) as usize;
                 let idx = (y * MAPWIDTH) + x;
-                if !spawn_points.contains_key(&idx) {
-                    spawn_points.insert(idx, spawn_table.roll(&mut rng));
+                if let Entry::Vacant(spot) = spawn_points.entry(idx) {
+                    // Anything already at its cap for this room rerolls
+                    let name = room_table.roll(&mut rng);
+                    let count = placed.entry(name.clone()).or_insert(0);
+                    if caps.get(&name).is_some_and(|cap| *count >= *cap) {
+                        tries += 1;
+                        continue;
+                    }
+                    *count += 1;
+                    spot.insert(name);
                     added = true;
                 } else {
                     tries += 1;
@@ -65,95 +108,301 @@ pub fn spawn_room(ecs: &mut World, room: &RoomRect, depth: i32) {
         }
     }
 
+    let mut mobs : Vec<Entity> = Vec::new();
     for spawn in spawn_points.iter() {
         let x = (*spawn.0 % MAPWIDTH) as i32;
         let y = (*spawn.0 / MAPWIDTH) as i32;
 
-        match spawn.1.as_ref() {
-            "Goblin" => goblin(ecs, x, y),
-            "Orc" => orc(ecs, x, y),
-            "Health Potion" => health_potion(ecs, x, y),
-            "Spike Trap" => spike_trap(ecs, x, y),
-            _ => {}
+        if spawn.1 == "Gold" {
+            let amount = ecs.write_resource::<RandomNumberGenerator>().roll_dice(2, 6) + depth;
+            gold_pile(ecs, x, y, amount);
+            continue;
+        }
+        if let Some(entity) = spawn_named_entity(ecs, spawn.1, x, y) {
+            if ecs.read_storage::<Mob>().get(entity).is_some() {
+                mobs.push(entity);
+            }
         }
     }
+
+    form_packs(ecs, &mobs);
 }
 
-fn room_random_table(depth: i32) -> RandomTable {
-    RandomTable::new()
-        .add("Goblin", 10)
-        .add("Orc", 1 + depth)
-        .add("Health Potion", 7)
-        .add("Spike Trap", 100 + depth)
+// Mobs of one faction spawned in the same room hunt as a pack, led by the first of them
+fn form_packs(ecs: &mut World, mobs: &[Entity]) {
+    let factions = ecs.read_storage::<Faction>();
+    let mut members = ecs.write_storage::<GroupMember>();
+    let mut leaders : HashMap<String, Entity> = HashMap::new();
+
+    for mob in mobs.iter() {
+        if let Some(faction) = factions.get(*mob) {
+            let leader = *leaders.entry(faction.name.clone()).or_insert(*mob);
+            members.insert(*mob, GroupMember{ leader }).expect("Unable to insert GroupMember");
+        }
+    }
 }
 
-// Spawnables
-fn orc(ecs: &mut World, x: i32, y: i32) { monster(ecs, x, y, to_cp437('o'), "Orc"); }
-fn goblin(ecs: &mut World, x: i32, y: i32) { monster(ecs, x, y, to_cp437('g'), "Goblin"); }
+// One of the raws' spawn tables, weighted for this depth
+fn spawn_table(ecs: &World, table: SpawnTable, depth: i32) -> RandomTable {
+    ecs.fetch::<RawMaster>().spawn_weights(table, depth).into_iter()
+        .fold(RandomTable::new(), |random_table, (name, weight)| random_table.add(name, weight))
+}
 
-fn monster(ecs: &mut World, x: i32, y:i32, glyph: FontCharType, name : &str) {
-    ecs
+// Everything in the raws is spawned through here, by name
+pub fn spawn_named_entity(ecs: &mut World, name: &str, x: i32, y: i32) -> Option<Entity> {
+    let raw = ecs.fetch::<RawMaster>().get(name)?;
+    let entity = match raw {
+        RawEntity::Mob(mob) => spawn_mob(ecs, &mob, x, y),
+        RawEntity::Item(item) => spawn_item(ecs, &item, x, y),
+        RawEntity::Trap(trap) => spawn_trap(ecs, &trap, x, y),
+        RawEntity::Prop(prop) => spawn_prop(ecs, &prop, x, y)
+    };
+    Some(entity)
+}
+
+fn raw_renderable(glyph: char, fg: &str, render_order: i32) -> Renderable {
+    Renderable{
+        glyph: to_cp437(glyph),
+        fg: RGB::from_hex(fg).expect("Unable to parse raw color"),
+        bg: RGB::named(BLACK),
+        render_order
+    }
+}
+
+fn spawn_mob(ecs: &mut World, raw: &RawMob, x: i32, y: i32) -> Entity {
+    // Some mobs are caught napping, most carry a few coins
+    let (state, gold) = {
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        let state = if rng.roll_dice(1, 3) == 1 { AwarenessState::Asleep } else { AwarenessState::Unaware };
+        (state, rng.roll_dice(1, 6) - 1)
+    };
+    let attributes = Attributes::new(raw.attributes.might, raw.attributes.agility, raw.attributes.wits, raw.attributes.vigor);
+    let max_hp = max_hp(attributes.vigor.value(), 1);
+
+    let mut builder = ecs
         .create_entity()
         .with(Position{ x, y })
-        .with(Renderable{
-            glyph: glyph,
-            fg: RGB::named(RED),
-            bg: RGB::named(BLACK),
-            render_order: 1,
-        })
-        .with(Viewshed{ visible_tiles : Vec::new(), range: 8, dirty: true })
+        .with(raw_renderable(raw.glyph, &raw.fg, 1))
+        .with(Viewshed{ visible_tiles : Vec::new(), range: raw.vision.unwrap_or(8), dirty: true })
         .with(Mob{})
         .with(Controllable{ current: false})
-        .with(Name{ name : name.to_string() })
+        .with(Name{ name : raw.name.clone() })
+        .with(Faction{ name : raw.faction.clone() })
+        .with(Awareness{ state, last_seen: None, turns_in_state: 0, perception: perception(attributes.wits.value()) })
         .with(BlocksTile{})
         .with(CombatStats{
-            attack: 5,
-            defense: 5,
-            evade: 0
+            attack: attack(attributes.might.value()),
+            defense: raw.defense.unwrap_or(5),
+            evade: evade(attributes.agility.value())
         })
         .with(PoolStats{
-            hp: SinglePoolStat { current: 8, max: 8 },
+            hp: SinglePoolStat { current: max_hp, max: max_hp },
             xp: 0,
             level: 1,
-            gold: 0
+            gold: raw.gold.unwrap_or(gold)
         })
-        .build();  
+        .with(attributes);
+
+    if let Some(weapon) = &raw.ranged_weapon {
+        builder = builder.with(weapon.clone());
+    }
+    if let Some(resistances) = &raw.resistances {
+        builder = builder.with(resistances.clone());
+    }
+    if let Some(ability) = &raw.ability {
+        builder = builder
+            .with(Ranged{ range: ability.range })
+            .with(ability.damage.clone());
+        if let Some(radius) = ability.radius {
+            builder = builder.with(AreaOfEffect{ radius });
+        }
+        if let Some(condition) = &ability.condition {
+            builder = builder.with(condition.clone());
+        }
+        if ability.single_use {
+            builder = builder.with(Consumable::new(1));
+        }
+    }
+    let mob = builder.build();
+
+    if let Some(level) = raw.level {
+        set_level(ecs, mob, level);
+    }
+    mob
 }
 
-fn health_potion(ecs: &mut World, x : i32, y : i32) {
-    ecs
-        .create_entity()
+// Spawns a mob straight at a higher level, at full health for it
+fn set_level(ecs: &mut World, entity: Entity, level: i32) {
+    let attributes = ecs.read_storage::<Attributes>();
+    let mut pool_stats = ecs.write_storage::<PoolStats>();
+    if let (Some(attributes), Some(pool)) = (attributes.get(entity), pool_stats.get_mut(entity)) {
+        pool.level = level;
+        pool.hp.max = max_hp(attributes.vigor.value(), level);
+        pool.hp.current = pool.hp.max;
+    }
+}
+
+pub fn corpse(ecs: &mut World, x: i32, y: i32, name: &str) {
+    ecs.create_entity()
         .with(Position{x, y})
         .with(Renderable{
-            glyph: to_cp437('¡'),
-            fg: RGB::named(MAGENTA),
+            glyph: to_cp437('%'),
+            fg: RGB::named(DARK_RED),
             bg: RGB::named(BLACK),
-            render_order: 2
-        })
-        .with(Name{ name: "Health Potion".to_string()})
-        .with(Item{})
-        .with(Consumable{ charges: 1})
-        .with(Heals{
-            amount: 8
+            render_order: 3
         })
+        .with(Name{ name: format!("{} corpse", name) })
         .build();
 }
 
-fn spike_trap(ecs: &mut World, x: i32, y: i32) {
+pub fn drop_loot(ecs: &mut World, x: i32, y: i32, depth: i32) {
+    let roll = {
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        spawn_table(ecs, SpawnTable::Loot, depth).roll(&mut rng)
+    };
+
+    spawn_named_entity(ecs, &roll, x, y);
+}
+
+pub fn gold_pile(ecs: &mut World, x: i32, y: i32, amount: i32) {
     ecs.create_entity()
         .with(Position{x, y})
         .with(Renderable{
-            glyph: to_cp437('^'),
-            fg: RGB::named(RED),
+            glyph: to_cp437('$'),
+            fg: RGB::named(GOLD),
             bg: RGB::named(BLACK),
             render_order: 2
         })
-        .with(EntryTrigger{})
-        .with(InflictsDamage{ amount: 6})
-        .with(Name{name: "Spike Trap".to_string()})
+        .with(Name{ name: format!("{} gold", amount) })
+        .with(Item{})
+        .with(Gold{ amount })
         .build();
 }
 
+fn spawn_item(ecs: &mut World, raw: &RawItem, x: i32, y: i32) -> Entity {
+    let mut builder = ecs
+        .create_entity()
+        .with(Position{x, y})
+        .with(raw_renderable(raw.glyph, &raw.fg, 2))
+        .with(Name{ name: raw.name.clone() })
+        .with(Item{})
+        .with(Value{ gold: raw.value });
+
+    if raw.stackable {
+        builder = builder.with(Stackable{ quantity: 1 });
+    }
+    if let Some(charges) = raw.charges {
+        builder = builder.with(Consumable::new(charges));
+    }
+    if let Some(slot) = raw.equippable {
+        builder = builder.with(Equippable{ slot });
+    }
+    if let Some(max) = raw.durability {
+        builder = builder.with(Durability{ current: max, max });
+    }
+    if let Some(weapon) = &raw.melee_weapon {
+        builder = builder.with(weapon.clone());
+    }
+    if let Some(wearable) = &raw.wearable {
+        builder = builder.with(wearable.clone());
+    }
+    if let Some(weapon) = &raw.ranged_weapon {
+        builder = builder.with(weapon.clone());
+    }
+    if let Some(bonus) = &raw.attribute_bonus {
+        builder = builder.with(bonus.clone());
+    }
+    if let Some(heals) = &raw.heals {
+        builder = builder.with(heals.clone());
+    }
+    if let Some(ranged) = &raw.ranged {
+        builder = builder.with(ranged.clone());
+    }
+    if let Some(damage) = &raw.damage {
+        builder = builder.with(damage.clone());
+    }
+    if let Some(aoe) = &raw.area_of_effect {
+        builder = builder.with(aoe.clone());
+    }
+    if let Some(condition) = &raw.condition {
+        builder = builder.with(condition.clone());
+    }
+    for effect in raw.effects.iter() {
+        builder = match effect {
+            RawEffect::RemovesCurse => builder.with(RemovesCurse{}),
+            RawEffect::Identifies => builder.with(Identifies{}),
+            RawEffect::MagicMapper => builder.with(MagicMapper{}),
+            RawEffect::TeleportSelf => builder.with(TeleportSelf{}),
+            RawEffect::ProvidesFood => builder.with(ProvidesFood{}),
+            RawEffect::Shatters => builder.with(Shatters{})
+        };
+    }
+    let item = builder.build();
+
+    if raw.equippable.is_some() {
+        maybe_curse(ecs, item);
+    }
+    obfuscate(ecs, item);
+    item
+}
+
+fn spawn_trap(ecs: &mut World, raw: &RawTrap, x: i32, y: i32) -> Entity {
+    let mut builder = ecs
+        .create_entity()
+        .with(Position{x, y})
+        .with(raw_renderable(raw.glyph, &raw.fg, 2))
+        .with(Name{ name: raw.name.clone() })
+        .with(EntryTrigger{});
+
+    if let Some(damage) = &raw.damage {
+        builder = builder.with(damage.clone());
+    }
+    if let Some(condition) = &raw.condition {
+        builder = builder.with(condition.clone());
+    }
+    builder.build()
+}
+
+fn spawn_prop(ecs: &mut World, raw: &RawProp, x: i32, y: i32) -> Entity {
+    let mut builder = ecs
+        .create_entity()
+        .with(Position{ x, y })
+        .with(raw_renderable(raw.glyph, &raw.fg, 1))
+        .with(Name{ name: raw.name.clone() });
+
+    if let Some(kind) = raw.vendor {
+        builder = builder.with(Vendor{ kind }).with(BlocksTile{});
+    }
+    builder.build()
+}
+
+// Some gear rolls cursed: much stronger, but it dulls the wits and cannot come off
+fn maybe_curse(ecs: &mut World, item: Entity) {
+    let cursed = {
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        rng.roll_dice(1, 6) == 1
+    };
+    if !cursed { return; }
+
+    if let Some(weapon) = ecs.write_storage::<MeleeWeapon>().get_mut(item) {
+        weapon.power += 3;
+    }
+    if let Some(wearable) = ecs.write_storage::<Wearable>().get_mut(item) {
+        wearable.armor += 2;
+    }
+    let mut bonuses = ecs.write_storage::<AttributeBonus>();
+    match bonuses.get_mut(item) {
+        Some(bonus) => {
+            bonus.might *= 2;
+            bonus.wits -= 3;
+        }
+        None => {
+            bonuses.insert(item, AttributeBonus{ wits: -3, ..Default::default() }).expect("Unable to insert AttributeBonus");
+        }
+    }
+    ecs.write_storage::<CursedItem>().insert(item, CursedItem{}).expect("Unable to insert CursedItem");
+}
+
 // Random Spawn Table system
 pub struct RandomEntry {
     name: String,
@@ -186,17 +435,45 @@ impl RandomTable {
     pub fn roll(&self, rng : &mut RandomNumberGenerator) -> String {
         if self.total_weight == 0 { return "None".to_string(); }
         let mut roll = rng.roll_dice(1, self.total_weight)-1;
-        let mut index : usize = 0;
 
-        while roll > 0 {
-            if roll < self.entries[index].weight {
-                return self.entries[index].name.clone();
+        for entry in self.entries.iter() {
+            if roll < entry.weight {
+                return entry.name.clone();
             }
-
-            roll -= self.entries[index].weight;
-            index += 1;
+            roll -= entry.weight;
         }
 
         "None".to_string()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roll_can_land_on_the_first_entry() {
+        // A single entry always rolls 0, which used to fall through to "None"
+        let mut rng = RandomNumberGenerator::seeded(1);
+        let table = RandomTable::new().add("Goblin", 1);
+        for _ in 0 .. 10 {
+            assert_eq!(table.roll(&mut rng), "Goblin");
+        }
+    }
+
+    #[test]
+    fn roll_reaches_every_entry() {
+        let mut rng = RandomNumberGenerator::seeded(7);
+        let table = RandomTable::new().add("Goblin", 1).add("Orc", 1).add("Ghost", 0);
+        let rolls : Vec<String> = (0 .. 100).map(|_| table.roll(&mut rng)).collect();
+        assert!(rolls.iter().any(|r| r == "Goblin"));
+        assert!(rolls.iter().any(|r| r == "Orc"));
+        assert!(rolls.iter().all(|r| r != "Ghost" && r != "None"));
+    }
+
+    #[test]
+    fn empty_table_rolls_none() {
+        let mut rng = RandomNumberGenerator::seeded(1);
+        assert_eq!(RandomTable::new().roll(&mut rng), "None");
+    }
+}
@@ -0,0 +1,78 @@
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+use crate::components::*;
+
+use super::{GameLog, RandomTable};
+
+fn upgrade_random_table() -> RandomTable {
+    RandomTable::new()
+        .add(Upgrade::Toughness.name(), 10)
+        .add(Upgrade::KeenEyes.name(), 6)
+        .add(Upgrade::SecondWind.name(), 4)
+        .add(Upgrade::CurseWard.name(), 4)
+}
+
+// Three different upgrades to pick from
+pub fn roll_upgrades(ecs: &mut World) -> [Upgrade; 3] {
+    let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+    let table = upgrade_random_table();
+
+    let mut choices : Vec<Upgrade> = Vec::new();
+    while choices.len() < 3 {
+        let roll = table.roll(&mut rng);
+        if let Some(upgrade) = UPGRADES.iter().find(|u| u.name() == roll) {
+            if !choices.contains(upgrade) {
+                choices.push(*upgrade);
+            }
+        }
+    }
+    [choices[0], choices[1], choices[2]]
+}
+
+pub fn apply_upgrade(ecs: &mut World, upgrade: Upgrade) {
+    let player_entity = ecs.fetch::<Entity>();
+    let mut upgrades = ecs.write_storage::<Upgrades>();
+    let mut log = ecs.fetch_mut::<GameLog>();
+
+    match upgrade {
+        Upgrade::Toughness => {
+            let mut attributes = ecs.write_storage::<Attributes>();
+            let mut pool_stats = ecs.write_storage::<PoolStats>();
+            let mut recalculate = ecs.write_storage::<RecalculateStats>();
+            if let (Some(attributes), Some(pool)) = (attributes.get_mut(*player_entity), pool_stats.get_mut(*player_entity)) {
+                attributes.vigor.base += 2;
+                pool.hp.current += 4;
+                recalculate.insert(*player_entity, RecalculateStats{}).expect("Unable to insert RecalculateStats");
+            }
+        }
+        Upgrade::KeenEyes => {
+            let mut viewsheds = ecs.write_storage::<Viewshed>();
+            if let Some(viewshed) = viewsheds.get_mut(*player_entity) {
+                viewshed.range += 2;
+                viewshed.dirty = true;
+            }
+        }
+        // These are looked up when they matter
+        Upgrade::SecondWind | Upgrade::CurseWard => {}
+    }
+
+    if let Some(upgrades) = upgrades.get_mut(*player_entity) {
+        upgrades.taken.push(upgrade);
+    }
+    log.entries.push(format!("You gain {}: {}.", upgrade.name(), upgrade.description()));
+}
+
+pub fn second_wind(ecs: &mut World) {
+    let player_entity = ecs.fetch::<Entity>();
+    let upgrades = ecs.read_storage::<Upgrades>();
+    let mut pool_stats = ecs.write_storage::<PoolStats>();
+    let mut log = ecs.fetch_mut::<GameLog>();
+
+    let stacks = upgrades.get(*player_entity).map_or(0, |u| u.count(Upgrade::SecondWind));
+    if stacks == 0 { return; }
+    if let Some(pool) = pool_stats.get_mut(*player_entity) {
+        pool.hp.current = i32::min(pool.hp.max, pool.hp.current + pool.hp.max * stacks / 4);
+        log.entries.push("You catch your second wind.".to_string());
+    }
+}
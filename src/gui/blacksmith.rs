@@ -0,0 +1,83 @@
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+use crate::{components::*, game::{BlacksmithService, BLACKSMITH_SERVICES, service_price}};
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum BlacksmithResult { Cancel, NoResponse, Service(BlacksmithService), Selected(BlacksmithService, Entity) }
+
+// First pick a service, then the worn item to have it done on
+pub fn show_blacksmith(ecs: &World, ctx: &mut BTerm, service: Option<BlacksmithService>) -> BlacksmithResult {
+    let player_entity = ecs.fetch::<Entity>();
+    let pool_stats = ecs.read_storage::<PoolStats>();
+    let gold = pool_stats.get(*player_entity).map_or(0, |stats| stats.gold);
+
+    ctx.draw_box(10, 12, 60, 14, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_color(12, 12, RGB::named(ORANGE), RGB::named(BLACK), "Ekileugor Guild of Blacksmiths");
+    ctx.print_color(12, 26, RGB::named(GOLD), RGB::named(BLACK), format!("{} gold - Escape to go back", gold));
+
+    match service {
+        None => pick_service(ctx),
+        Some(service) => pick_item(ecs, ctx, service, gold)
+    }
+}
+
+fn pick_service(ctx: &mut BTerm) -> BlacksmithResult {
+    for (j, service) in BLACKSMITH_SERVICES.iter().enumerate() {
+        draw_option(ctx, j, format!("{} - {}", service.name(), service.description()), RGB::named(WHITE));
+    }
+
+    match ctx.key {
+        None => BlacksmithResult::NoResponse,
+        Some(VirtualKeyCode::Escape) => BlacksmithResult::Cancel,
+        Some(key) => {
+            let selection = letter_to_option(key);
+            if selection >= 0 && (selection as usize) < BLACKSMITH_SERVICES.len() {
+                BlacksmithResult::Service(BLACKSMITH_SERVICES[selection as usize])
+            } else {
+                BlacksmithResult::NoResponse
+            }
+        }
+    }
+}
+
+fn pick_item(ecs: &World, ctx: &mut BTerm, service: BlacksmithService, gold: i32) -> BlacksmithResult {
+    let active_entity = ecs.fetch::<ActiveEntity>();
+    let entities = ecs.entities();
+    let equipped = ecs.read_storage::<Equipped>();
+    let names = ecs.read_storage::<Name>();
+
+    let worn : Vec<(Entity, String)> = (&entities, &equipped, &names).join()
+        .filter(|(_, e, _)| e.owner == active_entity.target)
+        .map(|(item, _, name)| (item, name.name.clone()))
+        .collect();
+
+    ctx.print_color(12, 13, RGB::named(YELLOW), RGB::named(BLACK), service.name());
+    for (j, (item, name)) in worn.iter().enumerate() {
+        match service_price(ecs, service, *item) {
+            None => draw_option(ctx, j, format!("{} - n/a", name), RGB::named(GREY)),
+            Some(price) => draw_option(ctx, j, format!("{} - {} gold", name, price), if price <= gold { RGB::named(WHITE) } else { RGB::named(RED) })
+        }
+    }
+
+    match ctx.key {
+        None => BlacksmithResult::NoResponse,
+        Some(VirtualKeyCode::Escape) => BlacksmithResult::Cancel,
+        Some(key) => {
+            let selection = letter_to_option(key);
+            if selection >= 0 && (selection as usize) < worn.len() {
+                BlacksmithResult::Selected(service, worn[selection as usize].0)
+            } else {
+                BlacksmithResult::NoResponse
+            }
+        }
+    }
+}
+
+fn draw_option(ctx: &mut BTerm, j: usize, text: String, color: RGB) {
+    let y = 15 + j as i32;
+    ctx.set(12, y, RGB::named(WHITE), RGB::named(BLACK), to_cp437('('));
+    ctx.set(13, y, RGB::named(YELLOW), RGB::named(BLACK), 97 + j as FontCharType);
+    ctx.set(14, y, RGB::named(WHITE), RGB::named(BLACK), to_cp437(')'));
+    ctx.print_color(16, y, color, RGB::named(BLACK), text);
+}
@@ -0,0 +1,57 @@
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+use crate::{components::*, game::xp_for_level};
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum CharacterSheetResult { NoResponse, Close }
+
+pub fn character_sheet(ecs: &World, ctx: &mut BTerm) -> CharacterSheetResult {
+    let player_entity = ecs.fetch::<Entity>();
+    let names = ecs.read_storage::<Name>();
+    let attributes = ecs.read_storage::<Attributes>();
+    let pool_stats = ecs.read_storage::<PoolStats>();
+    let combat_stats = ecs.read_storage::<CombatStats>();
+    let upgrades = ecs.read_storage::<Upgrades>();
+
+    ctx.draw_box(10, 5, 50, 30, RGB::named(WHITE), RGB::named(BLACK));
+    let name = names.get(*player_entity).map_or("Player", |n| n.name.as_str());
+    ctx.print_color(12, 5, RGB::named(YELLOW), RGB::named(BLACK), name);
+
+    let mut y = 7;
+    if let Some(pool) = pool_stats.get(*player_entity) {
+        ctx.print(12, y, format!("Level {}   XP {}/{}", pool.level, pool.xp, xp_for_level(pool.level + 1)));
+        ctx.print(12, y + 1, format!("HP {}/{}", pool.hp.current, pool.hp.max));
+        y += 3;
+    }
+
+    if let Some(attributes) = attributes.get(*player_entity) {
+        for attribute in ATTRIBUTES {
+            let stat = attributes.get(attribute);
+            let shift = stat.bonus + stat.modifiers;
+            let color = if shift < 0 { RGB::named(RED) } else if shift > 0 { RGB::named(GREEN) } else { RGB::named(WHITE) };
+            ctx.print_color(12, y, color, RGB::named(BLACK), format!("{:<8} {}", attribute.name(), stat.value()));
+            y += 1;
+        }
+        y += 1;
+    }
+
+    if let Some(combat) = combat_stats.get(*player_entity) {
+        ctx.print(12, y, format!("Attack {}  Defense {}  Evade {}", combat.attack, combat.defense, combat.evade));
+        y += 2;
+    }
+
+    ctx.print_color(12, y, RGB::named(YELLOW), RGB::named(BLACK), "Upgrades");
+    y += 1;
+    for upgrade in upgrades.get(*player_entity).map_or(&Vec::new(), |u| &u.taken).iter() {
+        ctx.print(12, y, format!("{} - {}", upgrade.name(), upgrade.description()));
+        y += 1;
+    }
+
+    ctx.print_color(12, 35, RGB::named(MAGENTA), RGB::named(BLACK), "Escape to close");
+
+    match ctx.key {
+        Some(VirtualKeyCode::Escape) | Some(VirtualKeyCode::C) => CharacterSheetResult::Close,
+        _ => CharacterSheetResult::NoResponse
+    }
+}
@@ -0,0 +1,51 @@
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+use crate::components::*;
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum ItemMenuResult { Cancel, NoResponse, Selected }
+
+// Everything the active entity carries that can be worn, and what it is wearing now
+pub fn show_equipment(ecs: &World, ctx: &mut BTerm) -> (ItemMenuResult, Option<Entity>) {
+    let active_entity = ecs.fetch::<ActiveEntity>();
+    let entities = ecs.entities();
+    let items_owned = ecs.read_storage::<ItemOwned>();
+    let equippable = ecs.read_storage::<Equippable>();
+    let equipped = ecs.read_storage::<Equipped>();
+    let cursed = ecs.read_storage::<CursedItem>();
+    let names = ecs.read_storage::<Name>();
+
+    let gear : Vec<(Entity, &Equippable, &Name)> = (&entities, &items_owned, &equippable, &names).join()
+        .filter(|(_, owned, _, _)| owned.owner == active_entity.target)
+        .map(|(item, _, slot, name)| (item, slot, name))
+        .collect();
+
+    let y = (25 - (gear.len() / 2)) as i32;
+    ctx.draw_box(15, y - 2, 40, (gear.len() + 3) as i32, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_color(18, y - 2, RGB::named(YELLOW), RGB::named(BLACK), "Equipment");
+    ctx.print_color(18, y + gear.len() as i32 + 1, RGB::named(YELLOW), RGB::named(BLACK), "Escape to cancel");
+
+    for (j, (item, slot, name)) in gear.iter().enumerate() {
+        let row = y + j as i32;
+        let worn = equipped.get(*item).is_some();
+        ctx.set(17, row, RGB::named(WHITE), RGB::named(BLACK), to_cp437('('));
+        ctx.set(18, row, RGB::named(YELLOW), RGB::named(BLACK), 97 + j as FontCharType);
+        ctx.set(19, row, RGB::named(WHITE), RGB::named(BLACK), to_cp437(')'));
+        let color = if !worn { RGB::named(WHITE) } else if cursed.get(*item).is_some() { RGB::named(RED) } else { RGB::named(GREEN) };
+        ctx.print_color(21, row, color, RGB::named(BLACK), format!("{} ({}){}", name.name, slot.slot.name(), if worn { " - worn" } else { "" }));
+    }
+
+    match ctx.key {
+        None => (ItemMenuResult::NoResponse, None),
+        Some(VirtualKeyCode::Escape) => (ItemMenuResult::Cancel, None),
+        Some(key) => {
+            let selection = letter_to_option(key);
+            if selection >= 0 && (selection as usize) < gear.len() {
+                (ItemMenuResult::Selected, Some(gear[selection as usize].0))
+            } else {
+                (ItemMenuResult::NoResponse, None)
+            }
+        }
+    }
+}
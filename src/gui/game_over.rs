@@ -0,0 +1,49 @@
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+use crate::{components::*, map::Map, game::score};
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum GameOverResult { NoSelection, Quit }
+
+fn cause_of_death(ecs: &World, death: &Dead) -> String {
+    let names = ecs.read_storage::<Name>();
+    if let Some(killer) = death.killer.and_then(|k| names.get(k)) {
+        return format!("Slain by {}", killer.name);
+    }
+
+    match death.cause {
+        DamageType::Physical => "Bled out".to_string(),
+        DamageType::Fire => "Burnt to a crisp".to_string(),
+        DamageType::Cold => "Frozen solid".to_string(),
+        DamageType::Poison => "Succumbed to poison".to_string(),
+        DamageType::Arcane => "Unmade by dark magic".to_string()
+    }
+}
+
+pub fn game_over(ecs: &World, ctx: &mut BTerm) -> GameOverResult {
+    let player_entity = ecs.fetch::<Entity>();
+    let map = ecs.fetch::<Map>();
+    let pool_stats = ecs.read_storage::<PoolStats>();
+    let deaths = ecs.read_storage::<Dead>();
+
+    ctx.draw_box(15, 15, 50, 10, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_color_centered(17, RGB::named(RED), RGB::named(BLACK), "Your journey has ended!");
+
+    if let Some(death) = deaths.get(*player_entity) {
+        let cause = format!("{} on depth {}.", cause_of_death(ecs, death), map.depth);
+        ctx.print_color_centered(19, RGB::named(WHITE), RGB::named(BLACK), &cause);
+    }
+    if let Some(stats) = pool_stats.get(*player_entity) {
+        let summary = format!("Level {}, {} xp, {} gold", stats.level, stats.xp, stats.gold);
+        ctx.print_color_centered(20, RGB::named(YELLOW), RGB::named(BLACK), &summary);
+        ctx.print_color_centered(21, RGB::named(GOLD), RGB::named(BLACK), format!("Score: {}", score(stats, map.depth)));
+    }
+
+    ctx.print_color_centered(23, RGB::named(MAGENTA), RGB::named(BLACK), "Press Escape to quit");
+
+    match ctx.key {
+        Some(VirtualKeyCode::Escape) => GameOverResult::Quit,
+        _ => GameOverResult::NoSelection
+    }
+}
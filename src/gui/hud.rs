@@ -1,7 +1,7 @@
 use bracket_lib::prelude::*;
 use specs::prelude::*;
 
-use crate::game::GameLog;
+use crate::game::{GameLog, xp_for_level};
 use crate::{components::*, map::Map};
 
 pub fn draw_ui(ecs: &World, ctx: &mut BTerm) {
@@ -12,6 +12,12 @@ pub fn draw_ui(ecs: &World, ctx: &mut BTerm) {
     let depth = format!("Depth: {}", map.depth);
     ctx.print_color(2, 43, RGB::named(YELLOW), RGB::named(BLACK), &depth);
 
+    let player_entity = ecs.fetch::<Entity>();
+    let pool_stats = ecs.read_storage::<PoolStats>();
+    if let Some(stats) = pool_stats.get(*player_entity) {
+        ctx.print_color(14, 43, RGB::named(GOLD), RGB::named(BLACK), format!("Gold: {}", stats.gold));
+    }
+
     let mut y = 44;
     for s in log.entries.iter().rev() {
         if y < 49 { ctx.print(2, y, s); }
@@ -20,6 +26,7 @@ pub fn draw_ui(ecs: &World, ctx: &mut BTerm) {
 
     draw_inventory(ecs, ctx);
     draw_pool_stats(ecs, ctx);
+    draw_conditions(ecs, ctx);
     draw_active_target(ecs, ctx);
 }
 
@@ -27,6 +34,10 @@ fn draw_inventory(ecs: &World, ctx: &mut BTerm) {
     let active_entity = ecs.fetch::<ActiveEntity>();
     let items_owned = ecs.read_storage::<ItemOwned>();
     let names = ecs.read_storage::<Name>();
+    let equipped = ecs.read_storage::<Equipped>();
+    let cursed = ecs.read_storage::<CursedItem>();
+    let consumables = ecs.read_storage::<Consumable>();
+    let stacks = ecs.read_storage::<Stackable>();
     let entities = ecs.entities();
 
     let inventory = (&items_owned, &names).join().filter(|item| active_entity.target.eq(&item.0.owner));
@@ -37,12 +48,18 @@ fn draw_inventory(ecs: &World, ctx: &mut BTerm) {
     ctx.print_color(66, y-2, RGB::named(YELLOW), RGB::named(BLACK), "Inventory");
 
     let mut j = 0;
-    for (_entity, _backpack, name) in (&entities, &items_owned, &names).join().filter(|item| active_entity.target.eq(&item.1.owner)) {
+    for (entity, _backpack, name) in (&entities, &items_owned, &names).join().filter(|item| active_entity.target.eq(&item.1.owner)) {
         ctx.set(66, y, RGB::named(WHITE), RGB::named(BLACK), to_cp437('('));
         ctx.set(67, y, RGB::named(YELLOW), RGB::named(BLACK), 97+j as FontCharType);
         ctx.set(68, y, RGB::named(WHITE), RGB::named(BLACK), to_cp437(')'));
 
-        ctx.print(69, y, &name.name.to_string());
+        // A curse only shows once it has been worn
+        let color = match (equipped.get(entity), cursed.get(entity)) {
+            (Some(_), Some(_)) => RGB::named(RED),
+            (Some(_), None) => RGB::named(GREEN),
+            _ => RGB::named(WHITE)
+        };
+        ctx.print_color(69, y, color, RGB::named(BLACK), item_label(name, consumables.get(entity), stacks.get(entity)));
         y += 1;
         j += 1;
     }
@@ -53,7 +70,7 @@ fn draw_pool_stats(ecs: &World, ctx: &mut BTerm) {
     let pool_stats = ecs.read_storage::<PoolStats>();
 
     let active_pool_stats = pool_stats.get(active_target.target).unwrap();
-    ctx.draw_box(65, 3, 14, 2, RGB::named(bracket_lib::color::ALICEBLUE), RGB::named(bracket_lib::color::BLACK));
+    ctx.draw_box(65, 3, 14, 3, RGB::named(bracket_lib::color::ALICEBLUE), RGB::named(bracket_lib::color::BLACK));
     ctx.print_color(
         66, 
         4, 
@@ -61,6 +78,45 @@ fn draw_pool_stats(ecs: &World, ctx: &mut BTerm) {
         RGB::named(bracket_lib::color::GREY), 
         &format!("HP {}/{}", active_pool_stats.hp.current.to_string(), active_pool_stats.hp.max.to_string())
     );
+
+    // Level, xp and hunger are the hero's own, whichever body we ride
+    let player_entity = ecs.fetch::<Entity>();
+    if let Some(player_stats) = pool_stats.get(*player_entity) {
+        ctx.print_color(66, 5, RGB::named(GOLD), RGB::named(BLACK), format!("L{} {}/{}", player_stats.level, player_stats.xp, xp_for_level(player_stats.level + 1)));
+    }
+}
+
+fn draw_conditions(ecs: &World, ctx: &mut BTerm) {
+    let active_target = ecs.fetch::<ActiveEntity>();
+    let player_entity = ecs.fetch::<Entity>();
+    let status_effects = ecs.read_storage::<StatusEffect>();
+    let hunger = ecs.read_storage::<HungerClock>();
+
+    let mut y = 7;
+    if let Some(clock) = hunger.get(*player_entity) {
+        let color = match clock.state {
+            HungerState::WellFed => Some(RGB::named(GREEN)),
+            HungerState::Normal => None,
+            HungerState::Hungry => Some(RGB::named(ORANGE)),
+            HungerState::Starving => Some(RGB::named(RED))
+        };
+        if let Some(color) = color {
+            ctx.print_color(66, y, color, RGB::named(BLACK), clock.state.name());
+            y += 1;
+        }
+    }
+    for effect in status_effects.join().filter(|effect| effect.target == active_target.target) {
+        let color = match effect.condition {
+            Condition::Poisoned => RGB::named(GREEN),
+            Condition::Burnt => RGB::named(ORANGE),
+            Condition::Frozen => RGB::named(CYAN),
+            Condition::Cursed => RGB::named(PURPLE),
+            Condition::Confused => RGB::named(PINK),
+            Condition::Afraid => RGB::named(YELLOW)
+        };
+        ctx.print_color(66, y, color, RGB::named(BLACK), format!("{} ({})", effect.condition.name(), effect.turns));
+        y += 1;
+    }
 }
 
 fn draw_active_target(ecs: &World, ctx: &mut BTerm) {
@@ -0,0 +1,181 @@
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+use crate::{components::*, game::{ItemAction, ITEM_ACTIONS, item_price}};
+
+use super::ItemMenuResult;
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum ItemActionResult { Cancel, NoResponse, Selected(ItemAction) }
+
+// Same order as the inventory panel, so the letters match
+pub fn show_inventory(ecs: &World, ctx: &mut BTerm) -> (ItemMenuResult, Option<Entity>) {
+    let active_entity = ecs.fetch::<ActiveEntity>();
+    let entities = ecs.entities();
+    let items_owned = ecs.read_storage::<ItemOwned>();
+    let names = ecs.read_storage::<Name>();
+    let consumables = ecs.read_storage::<Consumable>();
+    let stacks = ecs.read_storage::<Stackable>();
+
+    let inventory : Vec<(Entity, String)> = (&entities, &items_owned, &names).join()
+        .filter(|(_, owned, _)| owned.owner == active_entity.target)
+        .map(|(item, _, name)| (item, item_label(name, consumables.get(item), stacks.get(item))))
+        .collect();
+
+    let y = (25 - (inventory.len() / 2)) as i32;
+    ctx.draw_box(15, y - 2, 40, (inventory.len() + 3) as i32, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_color(18, y - 2, RGB::named(YELLOW), RGB::named(BLACK), "Inventory");
+    ctx.print_color(18, y + inventory.len() as i32 + 1, RGB::named(YELLOW), RGB::named(BLACK), "Escape to cancel");
+
+    for (j, (_, name)) in inventory.iter().enumerate() {
+        let row = y + j as i32;
+        ctx.set(17, row, RGB::named(WHITE), RGB::named(BLACK), to_cp437('('));
+        ctx.set(18, row, RGB::named(YELLOW), RGB::named(BLACK), 97 + j as FontCharType);
+        ctx.set(19, row, RGB::named(WHITE), RGB::named(BLACK), to_cp437(')'));
+        ctx.print(21, row, name);
+    }
+
+    match ctx.key {
+        None => (ItemMenuResult::NoResponse, None),
+        Some(VirtualKeyCode::Escape) => (ItemMenuResult::Cancel, None),
+        Some(key) => {
+            let selection = letter_to_option(key);
+            if selection >= 0 && (selection as usize) < inventory.len() {
+                (ItemMenuResult::Selected, Some(inventory[selection as usize].0))
+            } else {
+                (ItemMenuResult::NoResponse, None)
+            }
+        }
+    }
+}
+
+pub fn show_item_actions(ecs: &World, ctx: &mut BTerm, item: Entity) -> ItemActionResult {
+    let names = ecs.read_storage::<Name>();
+    let name = names.get(item).map_or("Item", |n| n.name.as_str());
+
+    let height = ITEM_ACTIONS.len() as i32 + 3;
+    ctx.draw_box(15, 20, 40, height, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_color(18, 20, RGB::named(YELLOW), RGB::named(BLACK), name);
+    ctx.print_color(18, 20 + height, RGB::named(YELLOW), RGB::named(BLACK), "Escape to go back");
+
+    for (j, action) in ITEM_ACTIONS.iter().enumerate() {
+        let row = 22 + j as i32;
+        ctx.set(17, row, RGB::named(WHITE), RGB::named(BLACK), to_cp437('('));
+        ctx.set(18, row, RGB::named(YELLOW), RGB::named(BLACK), 97 + j as FontCharType);
+        ctx.set(19, row, RGB::named(WHITE), RGB::named(BLACK), to_cp437(')'));
+        ctx.print(21, row, action.name());
+    }
+
+    match ctx.key {
+        None => ItemActionResult::NoResponse,
+        Some(VirtualKeyCode::Escape) => ItemActionResult::Cancel,
+        Some(key) => {
+            let selection = letter_to_option(key);
+            if selection >= 0 && (selection as usize) < ITEM_ACTIONS.len() {
+                ItemActionResult::Selected(ITEM_ACTIONS[selection as usize])
+            } else {
+                ItemActionResult::NoResponse
+            }
+        }
+    }
+}
+
+// Everything worth knowing about an item, one line per property
+fn describe_item(ecs: &World, item: Entity) -> Vec<String> {
+    let mut lines : Vec<String> = Vec::new();
+
+    // Nothing to go on but the look of it
+    if ecs.read_storage::<Unidentified>().get(item).is_some() {
+        lines.push("You don't know what this does yet.".to_string());
+        return lines;
+    }
+
+    if let Some(slot) = ecs.read_storage::<Equippable>().get(item) {
+        lines.push(format!("Worn on the {}", slot.slot.name()));
+    }
+    if let Some(weapon) = ecs.read_storage::<MeleeWeapon>().get(item) {
+        lines.push(format!("Melee power +{}", weapon.power));
+    }
+    if let Some(weapon) = ecs.read_storage::<RangedWeapon>().get(item) {
+        lines.push(format!("Shoots {} tiles, power +{}", weapon.range, weapon.power));
+    }
+    if let Some(wearable) = ecs.read_storage::<Wearable>().get(item) {
+        lines.push(format!("Armor +{}", wearable.armor));
+    }
+    if let Some(bonus) = ecs.read_storage::<AttributeBonus>().get(item) {
+        for attribute in ATTRIBUTES {
+            if bonus.get(attribute) != 0 {
+                lines.push(format!("{} {:+}", attribute.name(), bonus.get(attribute)));
+            }
+        }
+    }
+    if let Some(enchantment) = ecs.read_storage::<Enchantment>().get(item) {
+        lines.push(format!("Enchanted +{}", enchantment.level));
+    }
+    if let Some(durability) = ecs.read_storage::<Durability>().get(item) {
+        lines.push(format!("Durability {}/{}", durability.current, durability.max));
+    }
+    if let Some(heals) = ecs.read_storage::<Heals>().get(item) {
+        lines.push(format!("Heals {} hp", heals.amount));
+    }
+    if let Some(damage) = ecs.read_storage::<InflictsDamage>().get(item) {
+        lines.push(format!("Deals {} {:?} damage", damage.amount, damage.kind).to_lowercase());
+    }
+    if let Some(consumable) = ecs.read_storage::<Consumable>().get(item).filter(|c| c.max_charges > 1) {
+        lines.push(format!("{} of {} charges left", consumable.charges, consumable.max_charges));
+    }
+    if let Some(ranged) = ecs.read_storage::<Ranged>().get(item) {
+        lines.push(format!("Range {}", ranged.range));
+    }
+    if let Some(aoe) = ecs.read_storage::<AreaOfEffect>().get(item) {
+        lines.push(format!("Blast radius {}", aoe.radius));
+    }
+    if let Some(inflicts) = ecs.read_storage::<InflictsCondition>().get(item) {
+        lines.push(format!("Leaves the target {} for {} turns", inflicts.condition.name(), inflicts.turns));
+    }
+    if ecs.read_storage::<RemovesCurse>().get(item).is_some() {
+        lines.push("Lifts curses off your gear".to_string());
+    }
+    if ecs.read_storage::<Identifies>().get(item).is_some() {
+        lines.push("Reveals what one of your items is".to_string());
+    }
+    if ecs.read_storage::<MagicMapper>().get(item).is_some() {
+        lines.push("Reveals the layout of the level".to_string());
+    }
+    if ecs.read_storage::<TeleportSelf>().get(item).is_some() {
+        lines.push("Whisks you off somewhere else on the level".to_string());
+    }
+    if ecs.read_storage::<ProvidesFood>().get(item).is_some() {
+        lines.push("A filling meal".to_string());
+    }
+    if ecs.read_storage::<Shatters>().get(item).is_some() {
+        lines.push("Shatters over whoever it is thrown at".to_string());
+    }
+    // A curse only gives itself away once worn
+    if ecs.read_storage::<CursedItem>().get(item).is_some() && ecs.read_storage::<Equipped>().get(item).is_some() {
+        lines.push("Cursed!".to_string());
+    }
+    if let Some(price) = item_price(ecs, item) {
+        lines.push(format!("Worth {} gold", price));
+    }
+
+    lines
+}
+
+pub fn inspect_item(ecs: &World, ctx: &mut BTerm, item: Entity) -> ItemMenuResult {
+    let names = ecs.read_storage::<Name>();
+    let name = names.get(item).map_or("Item", |n| n.name.as_str());
+    let lines = describe_item(ecs, item);
+
+    ctx.draw_box(15, 15, 50, (lines.len() + 3) as i32, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_color(18, 15, RGB::named(YELLOW), RGB::named(BLACK), name);
+    for (j, line) in lines.iter().enumerate() {
+        ctx.print(17, 17 + j as i32, line);
+    }
+    ctx.print_color(18, 18 + lines.len() as i32, RGB::named(YELLOW), RGB::named(BLACK), "Escape to go back");
+
+    match ctx.key {
+        Some(VirtualKeyCode::Escape) => ItemMenuResult::Cancel,
+        _ => ItemMenuResult::NoResponse
+    }
+}
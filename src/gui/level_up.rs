@@ -0,0 +1,46 @@
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+use crate::components::*;
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum LevelUpResult { NoSelection, Selected(Attribute) }
+
+const CHOICES : [(Attribute, &str); 4] = [
+    (Attribute::Might, "hit harder"),
+    (Attribute::Agility, "dodge more often"),
+    (Attribute::Wits, "notice more, be noticed less"),
+    (Attribute::Vigor, "more hp")
+];
+
+pub fn level_up(ecs: &World, ctx: &mut BTerm) -> LevelUpResult {
+    let player_entity = ecs.fetch::<Entity>();
+    let attributes = ecs.read_storage::<Attributes>();
+    let pool_stats = ecs.read_storage::<PoolStats>();
+
+    let level = pool_stats.get(*player_entity).map_or(1, |stats| stats.level);
+    ctx.draw_box(15, 15, 50, 9, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_color_centered(16, RGB::named(YELLOW), RGB::named(BLACK), format!("Level {}! Choose what to raise", level + 1));
+
+    let attributes = attributes.get(*player_entity);
+    for (j, (attribute, hint)) in CHOICES.iter().enumerate() {
+        let y = 18 + j as i32;
+        let value = attributes.map_or(0, |a| a.get(*attribute).value());
+        ctx.set(17, y, RGB::named(WHITE), RGB::named(BLACK), to_cp437('('));
+        ctx.set(18, y, RGB::named(YELLOW), RGB::named(BLACK), 97 + j as FontCharType);
+        ctx.set(19, y, RGB::named(WHITE), RGB::named(BLACK), to_cp437(')'));
+        ctx.print(21, y, format!("{} {} - {}", attribute.name(), value, hint));
+    }
+
+    match ctx.key {
+        None => LevelUpResult::NoSelection,
+        Some(key) => {
+            let selection = letter_to_option(key);
+            if selection >= 0 && (selection as usize) < CHOICES.len() {
+                LevelUpResult::Selected(CHOICES[selection as usize].0)
+            } else {
+                LevelUpResult::NoSelection
+            }
+        }
+    }
+}
@@ -1,3 +1,21 @@
 mod hud;
+mod targeting;
+mod game_over;
+mod level_up;
+mod upgrade_choice;
+mod character_sheet;
+mod equipment;
+mod blacksmith;
+mod shop;
+mod inventory;
 
-pub use hud::*;
\ No newline at end of file
+pub use hud::*;
+pub use targeting::*;
+pub use game_over::*;
+pub use level_up::*;
+pub use upgrade_choice::*;
+pub use character_sheet::*;
+pub use equipment::*;
+pub use blacksmith::*;
+pub use shop::*;
+pub use inventory::*;
@@ -0,0 +1,71 @@
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+use crate::{components::*, game::{ShopMode, item_price, sell_price}};
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum ShopResult { Cancel, NoResponse, SwitchMode, Selected(Entity) }
+
+// Buying lists the merchant's stock, selling lists what the active entity carries
+pub fn show_shop(ecs: &World, ctx: &mut BTerm, vendor: Entity, mode: ShopMode) -> ShopResult {
+    let player_entity = ecs.fetch::<Entity>();
+    let active_entity = ecs.fetch::<ActiveEntity>();
+    let entities = ecs.entities();
+    let items_owned = ecs.read_storage::<ItemOwned>();
+    let names = ecs.read_storage::<Name>();
+    let consumables = ecs.read_storage::<Consumable>();
+    let stacks = ecs.read_storage::<Stackable>();
+    let pool_stats = ecs.read_storage::<PoolStats>();
+    let gold = pool_stats.get(*player_entity).map_or(0, |stats| stats.gold);
+
+    let owner = match mode {
+        ShopMode::Buy => vendor,
+        ShopMode::Sell => active_entity.target
+    };
+    let goods : Vec<(Entity, String, Option<i32>)> = (&entities, &items_owned, &names).join()
+        .filter(|(_, owned, _)| owned.owner == owner)
+        .map(|(item, _, name)| {
+            let price = match mode {
+                ShopMode::Buy => item_price(ecs, item),
+                ShopMode::Sell => sell_price(ecs, item)
+            };
+            (item, item_label(name, consumables.get(item), stacks.get(item)), price)
+        })
+        .collect();
+
+    ctx.draw_box(10, 10, 60, 20, RGB::named(WHITE), RGB::named(BLACK));
+    let title = match mode {
+        ShopMode::Buy => "Merchant - Buying (Tab to sell)",
+        ShopMode::Sell => "Merchant - Selling (Tab to buy)"
+    };
+    ctx.print_color(12, 10, RGB::named(YELLOW), RGB::named(BLACK), title);
+    ctx.print_color(12, 30, RGB::named(GOLD), RGB::named(BLACK), format!("{} gold - Escape to leave", gold));
+
+    for (j, (_, name, price)) in goods.iter().enumerate() {
+        let y = 12 + j as i32;
+        ctx.set(12, y, RGB::named(WHITE), RGB::named(BLACK), to_cp437('('));
+        ctx.set(13, y, RGB::named(YELLOW), RGB::named(BLACK), 97 + j as FontCharType);
+        ctx.set(14, y, RGB::named(WHITE), RGB::named(BLACK), to_cp437(')'));
+        match price {
+            None => ctx.print_color(16, y, RGB::named(GREY), RGB::named(BLACK), format!("{} - worthless", name)),
+            Some(price) => {
+                let color = if mode == ShopMode::Buy && *price > gold { RGB::named(RED) } else { RGB::named(WHITE) };
+                ctx.print_color(16, y, color, RGB::named(BLACK), format!("{} - {} gold", name, price));
+            }
+        }
+    }
+
+    match ctx.key {
+        None => ShopResult::NoResponse,
+        Some(VirtualKeyCode::Escape) => ShopResult::Cancel,
+        Some(VirtualKeyCode::Tab) => ShopResult::SwitchMode,
+        Some(key) => {
+            let selection = letter_to_option(key);
+            if selection >= 0 && (selection as usize) < goods.len() {
+                ShopResult::Selected(goods[selection as usize].0)
+            } else {
+                ShopResult::NoResponse
+            }
+        }
+    }
+}
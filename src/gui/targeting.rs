@@ -0,0 +1,78 @@
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+use crate::game::get_screen_bounds;
+use crate::{components::*, map::Map};
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum TargetingResult { Cancel, NoResponse, Selected }
+
+// Tiles the active entity can see and reach
+fn tiles_in_range(ecs: &World, range: i32) -> Vec<Point> {
+    let active_entity = ecs.fetch::<ActiveEntity>();
+    let viewsheds = ecs.read_storage::<Viewshed>();
+    let positions = ecs.read_storage::<Position>();
+
+    let mut available : Vec<Point> = Vec::new();
+    if let (Some(viewshed), Some(pos)) = (viewsheds.get(active_entity.target), positions.get(active_entity.target)) {
+        let origin = Point::new(pos.x, pos.y);
+        for tile in viewshed.visible_tiles.iter() {
+            if DistanceAlg::Pythagoras.distance2d(origin, *tile) <= range as f32 {
+                available.push(*tile);
+            }
+        }
+    }
+    available
+}
+
+pub fn show_targeting(ecs: &World, ctx: &mut BTerm, range: i32, radius: i32, cursor: Point) -> (TargetingResult, Point) {
+    let (min_x, _max_x, min_y, _max_y) = get_screen_bounds(ecs, ctx);
+    let map = ecs.fetch::<Map>();
+    let available = tiles_in_range(ecs, range);
+
+    ctx.print_color(5, 0, RGB::named(YELLOW), RGB::named(BLACK), "Select Target: arrows/click, Enter to confirm, Esc to cancel");
+
+    for tile in available.iter() {
+        ctx.set_bg(tile.x - min_x, tile.y - min_y, RGB::named(BLUE));
+    }
+
+    // Preview what a blast centered on the cursor would catch
+    if radius > 0 {
+        for tile in field_of_view(cursor, radius, &*map).iter() {
+            if tile.x >= 0 && tile.x < map.width && tile.y >= 0 && tile.y < map.height {
+                ctx.set_bg(tile.x - min_x, tile.y - min_y, RGB::named(ORANGE));
+            }
+        }
+    }
+
+    let cursor_valid = available.contains(&cursor);
+    ctx.set_bg(cursor.x - min_x, cursor.y - min_y, if cursor_valid { RGB::named(CYAN) } else { RGB::named(RED) });
+
+    let (mouse_x, mouse_y) = ctx.mouse_pos();
+    let mouse_map = Point::new(mouse_x + min_x, mouse_y + min_y);
+    if available.contains(&mouse_map) {
+        ctx.set_bg(mouse_x, mouse_y, RGB::named(CYAN));
+        if ctx.left_click {
+            return (TargetingResult::Selected, mouse_map);
+        }
+    }
+
+    // The cursor stops at the edge of the map
+    let step = |dx: i32, dy: i32| Point::new(
+        i32::clamp(cursor.x + dx, 0, map.width - 1),
+        i32::clamp(cursor.y + dy, 0, map.height - 1)
+    );
+
+    match ctx.key {
+        None => (TargetingResult::NoResponse, cursor),
+        Some(key) => match key {
+            VirtualKeyCode::Escape => (TargetingResult::Cancel, cursor),
+            VirtualKeyCode::Return | VirtualKeyCode::F if cursor_valid => (TargetingResult::Selected, cursor),
+            VirtualKeyCode::Left => (TargetingResult::NoResponse, step(-1, 0)),
+            VirtualKeyCode::Right => (TargetingResult::NoResponse, step(1, 0)),
+            VirtualKeyCode::Up => (TargetingResult::NoResponse, step(0, -1)),
+            VirtualKeyCode::Down => (TargetingResult::NoResponse, step(0, 1)),
+            _ => (TargetingResult::NoResponse, cursor)
+        }
+    }
+}
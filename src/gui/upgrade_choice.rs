@@ -0,0 +1,31 @@
+use bracket_lib::prelude::*;
+
+use crate::components::*;
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum UpgradeChoiceResult { NoSelection, Selected(Upgrade) }
+
+pub fn upgrade_choice(ctx: &mut BTerm, choices: &[Upgrade; 3]) -> UpgradeChoiceResult {
+    ctx.draw_box(10, 15, 60, 8, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_color_centered(16, RGB::named(YELLOW), RGB::named(BLACK), "The portal offers you a gift. Choose one");
+
+    for (j, upgrade) in choices.iter().enumerate() {
+        let y = 18 + j as i32;
+        ctx.set(12, y, RGB::named(WHITE), RGB::named(BLACK), to_cp437('('));
+        ctx.set(13, y, RGB::named(YELLOW), RGB::named(BLACK), 97 + j as FontCharType);
+        ctx.set(14, y, RGB::named(WHITE), RGB::named(BLACK), to_cp437(')'));
+        ctx.print(16, y, format!("{} - {}", upgrade.name(), upgrade.description()));
+    }
+
+    match ctx.key {
+        None => UpgradeChoiceResult::NoSelection,
+        Some(key) => {
+            let selection = letter_to_option(key);
+            if selection >= 0 && (selection as usize) < choices.len() {
+                UpgradeChoiceResult::Selected(choices[selection as usize])
+            } else {
+                UpgradeChoiceResult::NoSelection
+            }
+        }
+    }
+}
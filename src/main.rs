@@ -7,9 +7,10 @@ mod map;
 use map::*;
 mod game;
 use game::*;
-use systems::{remove_particles, ParticleBuilder};
+use systems::{remove_particles, ParticleBuilder, NoiseBuilder};
 mod gui;
 mod systems;
+mod raws;
 
 pub struct State {
     pub ecs: World,
@@ -17,7 +18,12 @@ pub struct State {
 
 #[derive(PartialEq, Copy, Clone)]
 pub enum RunState {
-    AwaitingInput, PreRun, PlayerTurn, MonsterTurn, CurseTurn, NextLevel
+    AwaitingInput, PreRun, PlayerTurn, MonsterTurn, CurseTurn, NextLevel, GameOver, LevelUp, CharacterSheet, ShowEquipment,
+    UpgradeChoice { choices: [Upgrade; 3] },
+    ShowBlacksmith { service: Option<BlacksmithService> },
+    ShowShop { vendor: Entity, mode: ShopMode },
+    ShowInventory, ShowItemActions { item: Entity }, InspectItem { item: Entity },
+    ShowTargeting { range: i32, radius: i32, item: Option<Entity>, cursor: Point, thrown: bool }
 }
 
 impl State {
@@ -26,14 +32,34 @@ impl State {
         fov.run_now(&self.ecs);
         let mut map_index = systems::MapIndexingSystem{};
         map_index.run_now(&self.ecs);
+        let mut awareness = systems::AwarenessSystem{};
+        awareness.run_now(&self.ecs);
+        let mut status_effects = systems::StatusEffectSystem{};
+        status_effects.run_now(&self.ecs);
+        let mut hunger = systems::HungerSystem{};
+        hunger.run_now(&self.ecs);
+        let mut attributes = systems::AttributeSystem{};
+        attributes.run_now(&self.ecs);
+        let mut mob_ai = systems::MobAI{};
+        mob_ai.run_now(&self.ecs);
         let mut melee_combat = systems::MeleeCombatSystem{};
         melee_combat.run_now(&self.ecs);
+        let mut ranged_combat = systems::RangedCombatSystem{};
+        ranged_combat.run_now(&self.ecs);
+        let mut throwing = systems::ThrowItemSystem{};
+        throwing.run_now(&self.ecs);
         let mut damage = systems::DamageSystem{};
         damage.run_now(&self.ecs);
         let mut inventory = systems::ItemPickupSystem{};
         inventory.run_now(&self.ecs);
+        let mut inventory_drop = systems::ItemDropSystem{};
+        inventory_drop.run_now(&self.ecs);
+        let mut inventory_equip = systems::ItemEquipSystem{};
+        inventory_equip.run_now(&self.ecs);
         let mut inventory_use = systems::ItemUseSystem{};
         inventory_use.run_now(&self.ecs);
+        let mut identification = systems::ItemIdentificationSystem{};
+        identification.run_now(&self.ecs);
         let mut particles = systems::ParticleSpawnSystem{};
         particles.run_now(&self.ecs);
         let mut trigger = systems::TriggerSystem{};
@@ -47,6 +73,7 @@ impl State {
         let entities = self.ecs.entities();
         let player = self.ecs.read_storage::<Player>();
         let stored_items = self.ecs.read_storage::<ItemOwned>();
+        let status_effects = self.ecs.read_storage::<StatusEffect>();
         let player_entity = self.ecs.fetch::<Entity>();
 
         let mut to_remove : Vec<Entity> = Vec::new();
@@ -67,6 +94,13 @@ impl State {
                 }
             }            
 
+            // Nor the hero's conditions
+            if let Some(effect) = status_effects.get(entity) {
+                if effect.target == *player_entity {
+                    should_remove = false;
+                }
+            }
+
             if should_remove {
                 to_remove.push(entity);
             }
@@ -93,6 +127,24 @@ impl State {
             spawn_room(&mut self.ecs, room, current_depth + 1);
         }
 
+        // The guild of Blacksmiths keeps a forge open every few levels, right by the way in
+        if (current_depth + 1) % 3 == 0 {
+            let (entry_x, entry_y) = map.rooms[0].center();
+            spawn_named_entity(&mut self.ecs, "Blacksmith", entry_x + 1, entry_y);
+        }
+
+        // Merchants set up shop at the entrance every other level
+        if (current_depth + 1) % 2 == 0 {
+            let (entry_x, entry_y) = map.rooms[0].center();
+            merchant(&mut self.ecs, entry_x - 1, entry_y, current_depth + 1);
+        }
+
+        // Nograd waits by the exit of his lair
+        if current_depth + 1 == NOGRAD_DEPTH {
+            let (exit_x, exit_y) = map.rooms[map.rooms.len() - 1].center();
+            spawn_named_entity(&mut self.ecs, "Nograd", exit_x - 1, exit_y);
+        }
+
         let (player_x, player_y) = map.rooms[0].center();
         let mut player_position = self.ecs.write_resource::<Point>();
         *player_position = Point::new(player_x, player_y);
@@ -115,7 +167,6 @@ impl State {
         }
         let mut log = self.ecs.fetch_mut::<GameLog>();
         log.entries.push("You reached the portal and moved on!".to_string()); 
-        // reset stats?
 
     }
 }
@@ -182,12 +233,131 @@ impl GameState for State {
             RunState::CurseTurn => {
                 self.run_systems();
                 self.ecs.maintain();
-                // try_curse(&mut self.ecs);
-                newrunstate = RunState::AwaitingInput;
+                try_curse(&mut self.ecs);
+                newrunstate = if game::can_level_up(&self.ecs) { RunState::LevelUp } else { RunState::AwaitingInput };
             }
             RunState::NextLevel => {
                 self.goto_next_level();
-                newrunstate = RunState::PreRun;
+                game::second_wind(&mut self.ecs);
+                newrunstate = RunState::UpgradeChoice{ choices: game::roll_upgrades(&mut self.ecs) };
+            }
+            RunState::LevelUp => {
+                if let gui::LevelUpResult::Selected(attribute) = gui::level_up(&self.ecs, ctx) {
+                    game::level_up(&mut self.ecs, attribute);
+                    if !game::can_level_up(&self.ecs) {
+                        newrunstate = RunState::AwaitingInput;
+                    }
+                }
+            }
+            RunState::UpgradeChoice{ choices } => {
+                if let gui::UpgradeChoiceResult::Selected(upgrade) = gui::upgrade_choice(ctx, &choices) {
+                    game::apply_upgrade(&mut self.ecs, upgrade);
+                    newrunstate = RunState::PreRun;
+                }
+            }
+            RunState::ShowInventory => {
+                let (result, item) = gui::show_inventory(&self.ecs, ctx);
+                match result {
+                    gui::ItemMenuResult::Cancel => newrunstate = RunState::AwaitingInput,
+                    gui::ItemMenuResult::NoResponse => {}
+                    gui::ItemMenuResult::Selected => {
+                        if let Some(item) = item {
+                            newrunstate = RunState::ShowItemActions{ item };
+                        }
+                    }
+                }
+            }
+            RunState::ShowItemActions{ item } => {
+                match gui::show_item_actions(&self.ecs, ctx, item) {
+                    gui::ItemActionResult::Cancel => newrunstate = RunState::ShowInventory,
+                    gui::ItemActionResult::NoResponse => {}
+                    gui::ItemActionResult::Selected(action) => newrunstate = game::item_action(&mut self.ecs, item, action)
+                }
+            }
+            RunState::InspectItem{ item } => {
+                if gui::inspect_item(&self.ecs, ctx, item) == gui::ItemMenuResult::Cancel {
+                    newrunstate = RunState::ShowItemActions{ item };
+                }
+            }
+            RunState::ShowEquipment => {
+                let (result, item) = gui::show_equipment(&self.ecs, ctx);
+                match result {
+                    gui::ItemMenuResult::Cancel => newrunstate = RunState::AwaitingInput,
+                    gui::ItemMenuResult::NoResponse => {}
+                    gui::ItemMenuResult::Selected => {
+                        if let Some(item) = item {
+                            let active_entity = self.ecs.fetch::<ActiveEntity>();
+                            let mut intent = self.ecs.write_storage::<EquipItemIntent>();
+                            intent.insert(active_entity.target, EquipItemIntent{ item }).expect("Unable to insert intent");
+                            newrunstate = RunState::PlayerTurn;
+                        }
+                    }
+                }
+            }
+            RunState::ShowBlacksmith{ service } => {
+                match gui::show_blacksmith(&self.ecs, ctx, service) {
+                    gui::BlacksmithResult::NoResponse => {}
+                    gui::BlacksmithResult::Cancel => {
+                        newrunstate = match service {
+                            None => RunState::AwaitingInput,
+                            Some(_) => RunState::ShowBlacksmith{ service: None }
+                        };
+                    }
+                    gui::BlacksmithResult::Service(service) => newrunstate = RunState::ShowBlacksmith{ service: Some(service) },
+                    gui::BlacksmithResult::Selected(service, item) => game::perform_service(&mut self.ecs, service, item)
+                }
+            }
+            RunState::ShowShop{ vendor, mode } => {
+                match gui::show_shop(&self.ecs, ctx, vendor, mode) {
+                    gui::ShopResult::NoResponse => {}
+                    gui::ShopResult::Cancel => newrunstate = RunState::AwaitingInput,
+                    gui::ShopResult::SwitchMode => {
+                        let mode = if mode == ShopMode::Buy { ShopMode::Sell } else { ShopMode::Buy };
+                        newrunstate = RunState::ShowShop{ vendor, mode };
+                    }
+                    gui::ShopResult::Selected(item) => match mode {
+                        ShopMode::Buy => game::buy_item(&mut self.ecs, item),
+                        ShopMode::Sell => game::sell_item(&mut self.ecs, vendor, item)
+                    }
+                }
+            }
+            RunState::CharacterSheet => {
+                if gui::character_sheet(&self.ecs, ctx) == gui::CharacterSheetResult::Close {
+                    newrunstate = RunState::AwaitingInput;
+                }
+            }
+            RunState::GameOver => {
+                if gui::game_over(&self.ecs, ctx) == gui::GameOverResult::Quit {
+                    ctx.quit();
+                }
+            }
+            RunState::ShowTargeting{ range, radius, item, cursor, thrown } => {
+                let (result, new_cursor) = gui::show_targeting(&self.ecs, ctx, range, radius, cursor);
+                match result {
+                    gui::TargetingResult::Cancel => newrunstate = RunState::AwaitingInput,
+                    gui::TargetingResult::NoResponse => newrunstate = RunState::ShowTargeting{ range, radius, item, cursor: new_cursor, thrown },
+                    gui::TargetingResult::Selected => {
+                        newrunstate = RunState::AwaitingInput;
+                        match item {
+                            Some(item) if thrown => {
+                                if game::throw_item(&mut self.ecs, item, new_cursor) {
+                                    newrunstate = RunState::PlayerTurn;
+                                }
+                            }
+                            Some(item) => {
+                                let active_entity = self.ecs.fetch::<ActiveEntity>();
+                                let mut intent = self.ecs.write_storage::<UseItemIntent>();
+                                intent.insert(active_entity.target, UseItemIntent{ item, target: Some(new_cursor) }).expect("Unable to insert intent");
+                                newrunstate = RunState::PlayerTurn;
+                            }
+                            None => {
+                                if game::fire_at(&mut self.ecs, new_cursor) {
+                                    newrunstate = RunState::PlayerTurn;
+                                }
+                            }
+                        }
+                    }
+                }
             }
         }
 
@@ -198,11 +368,24 @@ impl GameState for State {
 
         ctx.print(1, 49, &format!("FPS: {}", ctx.fps));
 
-        systems::delete_the_dead(&mut self.ecs);
+        if systems::delete_the_dead(&mut self.ecs) {
+            let mut runwriter = self.ecs.write_resource::<RunState>();
+            *runwriter = RunState::GameOver;
+        }
     }
 }
 
 fn main() -> BError {
+    // `--spawn-odds <depth>` prints what every spawn table rolls at that depth, then quits
+    let args : Vec<String> = std::env::args().collect();
+    let odds_depth = args.iter().position(|arg| arg == "--spawn-odds")
+        .and_then(|i| args.get(i + 1))
+        .and_then(|depth| depth.parse::<i32>().ok());
+    if let Some(depth) = odds_depth {
+        raws::load_raws().print_spawn_odds(depth);
+        return Ok(());
+    }
+
     let mut context = BTermBuilder::simple80x50()
         .with_title("GMTK2023 - Ekileugor")
         .with_tile_dimensions(16, 16)
@@ -214,7 +397,7 @@ fn main() -> BError {
         ecs: World::new()
     };
 
-    let rng = bracket_lib::random::RandomNumberGenerator::new();
+    let mut rng = bracket_lib::random::RandomNumberGenerator::new();
 
     gamestate.ecs.register::<Position>();
     gamestate.ecs.register::<Renderable>();
@@ -222,11 +405,19 @@ fn main() -> BError {
     gamestate.ecs.register::<Mob>();
     gamestate.ecs.register::<Controllable>();
     gamestate.ecs.register::<Name>();
+    gamestate.ecs.register::<Faction>();
+    gamestate.ecs.register::<GroupMember>();
+    gamestate.ecs.register::<Awareness>();
+    gamestate.ecs.register::<Stealth>();
     gamestate.ecs.register::<Viewshed>();
 
     // Stats components
     gamestate.ecs.register::<SinglePoolStat>();
     gamestate.ecs.register::<SingleStat>();
+    gamestate.ecs.register::<Attributes>();
+    gamestate.ecs.register::<RecalculateStats>();
+    gamestate.ecs.register::<Upgrades>();
+    gamestate.ecs.register::<HungerClock>();
     gamestate.ecs.register::<CombatStats>();
     gamestate.ecs.register::<PoolStats>();
     
@@ -235,18 +426,48 @@ fn main() -> BError {
 
     // Combat components
     gamestate.ecs.register::<MeleeIntent>();
+    gamestate.ecs.register::<RangedIntent>();
+    gamestate.ecs.register::<RangedWeapon>();
     gamestate.ecs.register::<Damage>();
     gamestate.ecs.register::<InflictsDamage>();
+    gamestate.ecs.register::<Resistances>();
+    gamestate.ecs.register::<Dead>();
     gamestate.ecs.register::<Hidden>();
     gamestate.ecs.register::<EntryTrigger>();
     gamestate.ecs.register::<EntityMoved>();
+    gamestate.ecs.register::<StatusEffect>();
+    gamestate.ecs.register::<InflictsCondition>();
 
     gamestate.ecs.register::<Item>();
     gamestate.ecs.register::<ItemOwned>();
     gamestate.ecs.register::<UseItemIntent>();
     gamestate.ecs.register::<PickupItemIntent>();
+    gamestate.ecs.register::<DropItemIntent>();
+    gamestate.ecs.register::<ThrowItemIntent>();
     gamestate.ecs.register::<Consumable>();
+    gamestate.ecs.register::<Stackable>();
     gamestate.ecs.register::<Heals>();
+    gamestate.ecs.register::<Ranged>();
+    gamestate.ecs.register::<AreaOfEffect>();
+    gamestate.ecs.register::<Equippable>();
+    gamestate.ecs.register::<Equipped>();
+    gamestate.ecs.register::<EquipItemIntent>();
+    gamestate.ecs.register::<MeleeWeapon>();
+    gamestate.ecs.register::<Wearable>();
+    gamestate.ecs.register::<AttributeBonus>();
+    gamestate.ecs.register::<CursedItem>();
+    gamestate.ecs.register::<RemovesCurse>();
+    gamestate.ecs.register::<Identifies>();
+    gamestate.ecs.register::<MagicMapper>();
+    gamestate.ecs.register::<TeleportSelf>();
+    gamestate.ecs.register::<ProvidesFood>();
+    gamestate.ecs.register::<Shatters>();
+    gamestate.ecs.register::<Unidentified>();
+    gamestate.ecs.register::<Enchantment>();
+    gamestate.ecs.register::<Durability>();
+    gamestate.ecs.register::<Vendor>();
+    gamestate.ecs.register::<Value>();
+    gamestate.ecs.register::<Gold>();
 
     gamestate.ecs.register::<ParticleLifetime>();
 
@@ -260,6 +481,9 @@ fn main() -> BError {
         target: player_entity
     };
     
+    let raws = raws::load_raws();
+    gamestate.ecs.insert(game::Identification::new(&mut rng, &raws.disguised_items()));
+    gamestate.ecs.insert(raws);
     gamestate.ecs.insert(rng);
 
     for room in map.rooms.iter().skip(1) {
@@ -276,6 +500,8 @@ fn main() -> BError {
     gamestate.ecs.insert(map);
     gamestate.ecs.insert(RunState::PreRun);
     gamestate.ecs.insert(ParticleBuilder::new());
+    gamestate.ecs.insert(NoiseBuilder::new());
+    gamestate.ecs.insert(game::faction_table());
 
     main_loop(context, gamestate)
 }
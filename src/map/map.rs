@@ -10,7 +10,7 @@ pub const MAPCOUNT : usize = MAPHEIGHT * MAPWIDTH;
 
 #[derive(PartialEq, Copy, Clone, Serialize, Deserialize)]
 pub enum TileType {
-    Wall, Floor, Exit
+    Wall, Floor, Exit, Grass
 }
 
 #[derive(PartialEq, Copy, Clone, Serialize, Deserialize)]
@@ -96,6 +96,22 @@ impl Map {
         }
     }
 
+    // Dry grass catches fire from anything burning on or next to it
+    fn apply_grass_to_room(&mut self, room : &RoomRect, rng : &mut RandomNumberGenerator) {
+        for y in room.y1 +1 ..= room.y2 {
+            for x in room.x1 + 1 ..= room.x2 {
+                let idx = self.xy_idx(x, y);
+                if self.tiles[idx] == TileType::Floor && rng.roll_dice(1, 3) > 1 {
+                    self.tiles[idx] = TileType::Grass;
+                }
+            }
+        }
+    }
+
+    pub fn is_flammable(&self, idx: usize) -> bool {
+        self.tiles[idx] == TileType::Grass
+    }
+
     fn is_exit_valid(&self, x:i32, y:i32) -> bool {
         if x < 1 || x > self.width - 1 || y < 1 || y > self.height - 1 { return false };
         let idx = self.xy_idx(x, y);
@@ -147,6 +163,12 @@ impl Map {
             }
         }
 
+        for room in map.rooms.clone().iter().skip(1) {
+            if rng.roll_dice(1, 4) == 1 {
+                map.apply_grass_to_room(room, &mut rng);
+            }
+        }
+
         let exit_position = map.rooms[map.rooms.len() -1 ].center();
         let exit_idx = map.xy_idx(exit_position.0, exit_position.1);
         map.tiles[exit_idx] = TileType::Exit;
@@ -259,6 +281,11 @@ pub fn draw_map(ecs: &World, ctx : &mut BTerm) {
                     fg = RGB::from_f32(0., 1.0, 1.0);
                     bg = RGB::from_f32(0., 0., 0.);
                 }
+                TileType::Grass => {
+                    glyph = to_cp437('"');
+                    fg = RGB::from_f32(0.4, 0.6, 0.1);
+                    bg = RGB::from_f32(0., 0., 0.);
+                }
             }
             if !map.visible_tiles[idx] { fg = fg.to_greyscale() }
             ctx.set(x, y, fg, bg, glyph);
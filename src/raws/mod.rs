@@ -0,0 +1,242 @@
+use std::collections::{HashMap, HashSet};
+
+use bracket_lib::terminal::RGB;
+
+use crate::game::{Disguise, POTION_LOOKS};
+
+mod structs;
+pub use structs::*;
+
+// Everything that can be spawned by name, read from the raws at startup
+pub struct RawMaster {
+    raws: Raws,
+    index: HashMap<String, RawEntity>
+}
+
+#[derive(Clone)]
+pub enum RawEntity {
+    Mob(RawMob), Item(RawItem), Trap(RawTrap), Prop(RawProp)
+}
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum SpawnTable {
+    Rooms, Loot, Shop
+}
+
+pub const SPAWN_TABLES : [SpawnTable; 3] = [SpawnTable::Rooms, SpawnTable::Loot, SpawnTable::Shop];
+
+impl SpawnTable {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SpawnTable::Rooms => "Rooms",
+            SpawnTable::Loot => "Loot",
+            SpawnTable::Shop => "Shop"
+        }
+    }
+
+    // Entries the spawner handles itself for this table rather than spawning from the raws
+    pub fn special_entries(&self) -> &'static [&'static str] {
+        match self {
+            SpawnTable::Rooms => &["Gold", "Nothing"],
+            SpawnTable::Loot => &["Nothing"],
+            SpawnTable::Shop => &[]
+        }
+    }
+}
+
+impl RawMaster {
+    pub fn new(raws: Raws) -> RawMaster {
+        let mut index : HashMap<String, RawEntity> = HashMap::new();
+        index.extend(raws.mobs.iter().map(|mob| (mob.name.clone(), RawEntity::Mob(mob.clone()))));
+        index.extend(raws.items.iter().map(|item| (item.name.clone(), RawEntity::Item(item.clone()))));
+        index.extend(raws.traps.iter().map(|trap| (trap.name.clone(), RawEntity::Trap(trap.clone()))));
+        index.extend(raws.props.iter().map(|prop| (prop.name.clone(), RawEntity::Prop(prop.clone()))));
+        RawMaster{ raws, index }
+    }
+
+    pub fn get(&self, name: &str) -> Option<RawEntity> {
+        self.index.get(name).cloned()
+    }
+
+    // Potions and scrolls that go by a disguise until identified
+    pub fn disguised_items(&self) -> Vec<(String, Disguise)> {
+        self.raws.items.iter().filter_map(|item| item.disguise.map(|disguise| (item.name.clone(), disguise))).collect()
+    }
+
+    pub fn biome(&self, depth: i32) -> Option<&str> {
+        self.raws.biomes.iter()
+            .find(|biome| depth >= biome.min_depth && biome.max_depth.is_none_or(|max| depth <= max))
+            .map(|biome| biome.name.as_str())
+    }
+
+    fn entries(&self, table: SpawnTable) -> &[RawSpawnEntry] {
+        match table {
+            SpawnTable::Rooms => &self.raws.spawn_tables.rooms,
+            SpawnTable::Loot => &self.raws.spawn_tables.loot,
+            SpawnTable::Shop => &self.raws.spawn_tables.shop
+        }
+    }
+
+    // What a table can roll at this depth, leaving out anything with no weight there
+    pub fn spawn_weights(&self, table: SpawnTable, depth: i32) -> Vec<(String, i32)> {
+        let biome = self.biome(depth);
+        self.entries(table).iter()
+            .map(|entry| (entry.name.clone(), entry.weight_at(depth, biome)))
+            .filter(|(_, weight)| *weight > 0)
+            .collect()
+    }
+
+    pub fn room_caps(&self) -> HashMap<String, i32> {
+        self.raws.spawn_tables.rooms.iter()
+            .filter_map(|entry| entry.max_per_room.map(|cap| (entry.name.clone(), cap)))
+            .collect()
+    }
+
+    // Everything wrong with the raws, so a bad edit fails at startup instead of mid-run
+    fn validate(&self) -> Vec<String> {
+        let mut errors : Vec<String> = Vec::new();
+
+        let mut seen : HashSet<&str> = HashSet::new();
+        let spawnables = self.raws.mobs.iter().map(|m| (&m.name, &m.fg))
+            .chain(self.raws.items.iter().map(|i| (&i.name, &i.fg)))
+            .chain(self.raws.traps.iter().map(|t| (&t.name, &t.fg)))
+            .chain(self.raws.props.iter().map(|p| (&p.name, &p.fg)));
+        for (name, fg) in spawnables {
+            if !seen.insert(name.as_str()) {
+                errors.push(format!("{} is defined more than once", name));
+            }
+            if RGB::from_hex(fg).is_err() {
+                errors.push(format!("{} has an unreadable colour {}", name, fg));
+            }
+        }
+
+        // Every potion needs a look of its own
+        let potions = self.raws.items.iter().filter(|item| item.disguise == Some(Disguise::Potion)).count();
+        if potions > POTION_LOOKS.len() {
+            errors.push(format!("{} disguised potions but only {} potion looks", potions, POTION_LOOKS.len()));
+        }
+
+        for biome in self.raws.biomes.iter() {
+            if biome.max_depth.is_some_and(|max| max < biome.min_depth) {
+                errors.push(format!("Biome {} ends before it starts", biome.name));
+            }
+        }
+
+        for table in SPAWN_TABLES {
+            for entry in self.entries(table).iter() {
+                let label = format!("{} table entry {}", table.name(), entry.name);
+                if !self.index.contains_key(&entry.name) && !table.special_entries().contains(&entry.name.as_str()) {
+                    errors.push(format!("{} is not in the raws", label));
+                }
+                if entry.weight < 0 {
+                    errors.push(format!("{} has a negative weight", label));
+                }
+                if entry.min_depth < 1 || entry.max_depth.is_some_and(|max| max < entry.min_depth) {
+                    errors.push(format!("{} has an impossible depth range", label));
+                }
+                if entry.max_per_room.is_some_and(|cap| cap < 1) {
+                    errors.push(format!("{} is capped below one per room", label));
+                }
+                for biome in entry.biomes.iter().flatten() {
+                    if !self.raws.biomes.iter().any(|b| b.name == *biome) {
+                        errors.push(format!("{} names unknown biome {}", label, biome));
+                    }
+                }
+            }
+        }
+        errors
+    }
+
+    // Debug view of the odds for every table at one depth
+    pub fn print_spawn_odds(&self, depth: i32) {
+        println!("Depth {} ({})", depth, self.biome(depth).unwrap_or("no biome"));
+        let caps = self.room_caps();
+        for table in SPAWN_TABLES {
+            let mut weights = self.spawn_weights(table, depth);
+            weights.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+            let total : i32 = weights.iter().map(|(_, weight)| weight).sum();
+
+            println!();
+            println!("{} (total weight {})", table.name(), total);
+            if total == 0 {
+                println!("  nothing can spawn");
+            }
+            for (name, weight) in weights.iter() {
+                let cap = match caps.get(name) {
+                    Some(cap) if table == SpawnTable::Rooms => format!(", max {} per room", cap),
+                    _ => String::new()
+                };
+                println!("  {:<22} {:>5.1}%  (weight {}{})", name, 100.0 * *weight as f32 / total as f32, weight, cap);
+            }
+        }
+    }
+}
+
+pub fn load_raws() -> RawMaster {
+    let raws : Raws = serde_json::from_str(include_str!("../../raws/spawns.json")).expect("Unable to parse raws");
+    let master = RawMaster::new(raws);
+    let errors = master.validate();
+    if !errors.is_empty() {
+        panic!("Invalid raws:\n{}", errors.join("\n"));
+    }
+    master
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(json: &str) -> RawSpawnEntry {
+        serde_json::from_str(json).expect("Unable to parse entry")
+    }
+
+    fn master_with_shop(shop: &str) -> RawMaster {
+        let json = format!(r#"{{ "mobs": [], "items": [], "traps": [], "props": [], "biomes": [],
+            "spawn_tables": {{ "rooms": [], "loot": [], "shop": {} }} }}"#, shop);
+        RawMaster::new(serde_json::from_str(&json).expect("Unable to parse raws"))
+    }
+
+    #[test]
+    fn shipped_raws_are_valid() {
+        let master = load_raws();
+        assert!(master.validate().is_empty());
+    }
+
+    #[test]
+    fn weight_respects_depth_range() {
+        let brute = entry(r#"{ "name": "Orc Brute", "weight": 1, "min_depth": 4, "max_depth": 6 }"#);
+        assert_eq!(brute.weight_at(3, None), 0);
+        assert_eq!(brute.weight_at(4, None), 1);
+        assert_eq!(brute.weight_at(6, None), 1);
+        assert_eq!(brute.weight_at(7, None), 0);
+    }
+
+    #[test]
+    fn weight_grows_per_depth_from_min_depth() {
+        let slime = entry(r#"{ "name": "Lava Slime", "weight": 1, "per_depth": 2, "min_depth": 3 }"#);
+        assert_eq!(slime.weight_at(3, None), 1);
+        assert_eq!(slime.weight_at(5, None), 5);
+
+        let fading = entry(r#"{ "name": "Goblin", "weight": 3, "per_depth": -2 }"#);
+        assert_eq!(fading.weight_at(3, None), 0);
+    }
+
+    #[test]
+    fn weight_respects_biomes() {
+        let rune = entry(r#"{ "name": "Curse Rune", "weight": 2, "biomes": ["Forge"] }"#);
+        assert_eq!(rune.weight_at(8, Some("Forge")), 2);
+        assert_eq!(rune.weight_at(8, Some("Caves")), 0);
+        assert_eq!(rune.weight_at(8, None), 0);
+
+        let anywhere = entry(r#"{ "name": "Goblin", "weight": 2 }"#);
+        assert_eq!(anywhere.weight_at(8, None), 2);
+    }
+
+    #[test]
+    fn validate_rejects_unknown_and_misplaced_entries() {
+        assert_eq!(master_with_shop(r#"[{ "name": "Nothing", "weight": 1 }]"#).validate().len(), 1);
+        assert_eq!(master_with_shop(r#"[{ "name": "Gold", "weight": 1 }]"#).validate().len(), 1);
+        assert_eq!(master_with_shop(r#"[{ "name": "Nonsense", "weight": 1, "biomes": ["Nowhere"] }]"#).validate().len(), 2);
+        assert!(master_with_shop("[]").validate().is_empty());
+    }
+}
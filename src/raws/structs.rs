@@ -0,0 +1,142 @@
+use serde::Deserialize;
+
+use crate::components::*;
+use crate::game::Disguise;
+
+#[derive(Deserialize, Debug)]
+pub struct Raws {
+    pub mobs: Vec<RawMob>,
+    pub items: Vec<RawItem>,
+    pub traps: Vec<RawTrap>,
+    pub props: Vec<RawProp>,
+    pub biomes: Vec<RawBiome>,
+    pub spawn_tables: RawSpawnTables
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct RawAttributes {
+    pub might: i32,
+    pub agility: i32,
+    pub wits: i32,
+    pub vigor: i32
+}
+
+// A breath or a burst the mob lets loose, fired through the same path as scrolls
+#[derive(Deserialize, Debug, Clone)]
+pub struct RawAbility {
+    pub range: i32,
+    pub radius: Option<i32>,
+    pub damage: InflictsDamage,
+    pub condition: Option<InflictsCondition>,
+    #[serde(default)]
+    pub single_use: bool
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct RawMob {
+    pub name: String,
+    pub glyph: char,
+    pub fg: String,
+    pub faction: String,
+    pub attributes: RawAttributes,
+    pub level: Option<i32>,
+    pub defense: Option<i32>,
+    pub gold: Option<i32>,
+    pub vision: Option<i32>,
+    pub ranged_weapon: Option<RangedWeapon>,
+    pub ability: Option<RawAbility>,
+    pub resistances: Option<Resistances>
+}
+
+// Marker components an item can carry, listed by name in the raws
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub enum RawEffect {
+    RemovesCurse, Identifies, MagicMapper, TeleportSelf, ProvidesFood, Shatters
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct RawItem {
+    pub name: String,
+    pub glyph: char,
+    pub fg: String,
+    pub value: i32,
+    pub disguise: Option<Disguise>,
+    #[serde(default)]
+    pub stackable: bool,
+    pub charges: Option<i32>,
+    pub equippable: Option<EquipmentSlot>,
+    pub durability: Option<i32>,
+    pub melee_weapon: Option<MeleeWeapon>,
+    pub wearable: Option<Wearable>,
+    pub ranged_weapon: Option<RangedWeapon>,
+    pub attribute_bonus: Option<AttributeBonus>,
+    pub heals: Option<Heals>,
+    pub ranged: Option<Ranged>,
+    pub damage: Option<InflictsDamage>,
+    pub area_of_effect: Option<AreaOfEffect>,
+    pub condition: Option<InflictsCondition>,
+    #[serde(default)]
+    pub effects: Vec<RawEffect>
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct RawTrap {
+    pub name: String,
+    pub glyph: char,
+    pub fg: String,
+    pub damage: Option<InflictsDamage>,
+    pub condition: Option<InflictsCondition>
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct RawProp {
+    pub name: String,
+    pub glyph: char,
+    pub fg: String,
+    pub vendor: Option<VendorKind>
+}
+
+// A stretch of depths that shares a look and its own spawns
+#[derive(Deserialize, Debug)]
+pub struct RawBiome {
+    pub name: String,
+    pub min_depth: i32,
+    pub max_depth: Option<i32>
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RawSpawnTables {
+    pub rooms: Vec<RawSpawnEntry>,
+    pub loot: Vec<RawSpawnEntry>,
+    pub shop: Vec<RawSpawnEntry>
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct RawSpawnEntry {
+    pub name: String,
+    pub weight: i32,
+    #[serde(default)]
+    pub per_depth: i32,
+    #[serde(default = "first_depth")]
+    pub min_depth: i32,
+    pub max_depth: Option<i32>,
+    pub biomes: Option<Vec<String>>,
+    pub max_per_room: Option<i32>
+}
+
+fn first_depth() -> i32 { 1 }
+
+impl RawSpawnEntry {
+    // Weight grows by per_depth for every level below min_depth, zero wherever it can't spawn
+    pub fn weight_at(&self, depth: i32, biome: Option<&str>) -> i32 {
+        if depth < self.min_depth || self.max_depth.is_some_and(|max| depth > max) {
+            return 0;
+        }
+        if let Some(biomes) = &self.biomes {
+            if !biome.is_some_and(|biome| biomes.iter().any(|b| b == biome)) {
+                return 0;
+            }
+        }
+        i32::max(0, self.weight + self.per_depth * (depth - self.min_depth))
+    }
+}
@@ -0,0 +1,73 @@
+use specs::prelude::*;
+
+use crate::{game::{attack, evade, max_hp, perception, stealth}, components::*};
+
+pub struct AttributeSystem {}
+
+// What each condition does to an attribute while it lasts
+fn condition_modifier(condition: Condition, attribute: Attribute) -> i32 {
+    match (condition, attribute) {
+        (Condition::Poisoned, Attribute::Might) => -2,
+        (Condition::Frozen, Attribute::Agility) => -4,
+        (Condition::Cursed, Attribute::Wits) => -2,
+        _ => 0
+    }
+}
+
+impl<'a> System<'a> for AttributeSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = ( Entities<'a>,
+                        WriteStorage<'a, Attributes>,
+                        WriteStorage<'a, RecalculateStats>,
+                        WriteStorage<'a, CombatStats>,
+                        WriteStorage<'a, PoolStats>,
+                        WriteStorage<'a, Awareness>,
+                        WriteStorage<'a, Stealth>,
+                        ReadStorage<'a, StatusEffect>,
+                        ReadStorage<'a, Equipped>,
+                        ReadStorage<'a, AttributeBonus> );
+
+    fn run(&mut self, data : Self::SystemData) {
+        let (entities, mut attributes, mut recalculate, mut combat_stats, mut pool_stats, mut awareness, mut stealth_skill, status_effects, equipped, attribute_bonus) = data;
+
+        for (entity, attributes, combat, pool) in (&entities, &mut attributes, &mut combat_stats, &mut pool_stats).join() {
+            // Equipping anything flags the owner, so only then is the gear worth adding up again
+            let mut dirty = recalculate.get(entity).is_some();
+            if dirty {
+                for attribute in ATTRIBUTES {
+                    attributes.get_mut(attribute).bonus = (&equipped, &attribute_bonus).join()
+                        .filter(|(e, _)| e.owner == entity)
+                        .map(|(_, bonus)| bonus.get(attribute))
+                        .sum();
+                }
+            }
+
+            // Conditions come and go without telling anyone, so check them every time
+            for attribute in ATTRIBUTES {
+                let modifiers = status_effects.join()
+                    .filter(|effect| effect.target == entity)
+                    .map(|effect| condition_modifier(effect.condition, attribute))
+                    .sum::<i32>();
+                let stat = attributes.get_mut(attribute);
+                if stat.modifiers != modifiers {
+                    stat.modifiers = modifiers;
+                    dirty = true;
+                }
+            }
+            if !dirty { continue; }
+
+            combat.attack = attack(attributes.might.value());
+            combat.evade = evade(attributes.agility.value());
+            pool.hp.max = max_hp(attributes.vigor.value(), pool.level);
+            pool.hp.current = i32::min(pool.hp.current, pool.hp.max);
+            if let Some(awareness) = awareness.get_mut(entity) {
+                awareness.perception = perception(attributes.wits.value());
+            }
+            if let Some(stealth_skill) = stealth_skill.get_mut(entity) {
+                stealth_skill.skill = stealth(attributes.wits.value());
+            }
+        }
+
+        recalculate.clear();
+    }
+}
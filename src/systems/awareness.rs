@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+
+use specs::prelude::*;
+use bracket_lib::prelude::*;
+
+use crate::{RunState, map::Map, components::*, game::{GameLog, FactionTable, effective_faction}};
+
+pub struct AwarenessSystem {}
+
+impl<'a> System<'a> for AwarenessSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = ( ReadExpect<'a, Map>,
+                        ReadExpect<'a, ActiveEntity>,
+                        ReadExpect<'a, RunState>,
+                        ReadExpect<'a, FactionTable>,
+                        WriteExpect<'a, RandomNumberGenerator>,
+                        WriteExpect<'a, NoiseBuilder>,
+                        WriteExpect<'a, GameLog>,
+                        Entities<'a>,
+                        ReadStorage<'a, Faction>,
+                        ReadStorage<'a, Viewshed>,
+                        ReadStorage<'a, Position>,
+                        ReadStorage<'a, Stealth>,
+                        ReadStorage<'a, Name>,
+                        ReadStorage<'a, GroupMember>,
+                        WriteStorage<'a, Awareness>);
+
+    fn run(&mut self, data : Self::SystemData) {
+        let (map, active_entity, runstate, faction_table, mut rng, mut noise, mut log, entities, factions, viewsheds, positions, stealth, names, groups, mut awarenesses) = data;
+
+        if *runstate != RunState::MonsterTurn { return; }
+
+        for (entity, faction, viewshed, pos, awareness) in (&entities, &factions, &viewsheds, &positions, &mut awarenesses).join() {
+            if entity == active_entity.target { continue; }
+
+            let my_pos = Point::new(pos.x, pos.y);
+
+            // Closest hostile in sight and how sneaky it is
+            let mut spotted : Option<(Entity, Point, f32, i32)> = None;
+            for (other, other_pos, other_stealth) in (&entities, &positions, stealth.maybe()).join() {
+                let other_point = Point::new(other_pos.x, other_pos.y);
+                if other == entity || !viewshed.visible_tiles.contains(&other_point) { continue; }
+                if let Some(their_faction) = effective_faction(other, &active_entity, &factions) {
+                    if faction_table.reaction(&faction.name, their_faction) != Reaction::Attack { continue; }
+                    let distance = DistanceAlg::Pythagoras.distance2d(my_pos, other_point);
+                    if spotted.is_none_or(|(_, _, d, _)| distance < d) {
+                        spotted = Some((other, other_point, distance, other_stealth.map_or(0, |s| s.skill)));
+                    }
+                }
+            }
+
+            let heard = noise.requests.iter()
+                .find(|n| DistanceAlg::Pythagoras.distance2d(my_pos, Point::new(n.x, n.y)) <= n.volume as f32)
+                .map(|n| Point::new(n.x, n.y));
+
+            let name = names.get(entity).map_or("Something", |n| n.name.as_str());
+            let in_sight = map.visible_tiles[map.xy_idx(pos.x, pos.y)];
+
+            match awareness.state {
+                AwarenessState::Asleep => {
+                    // Noise may wake a sleeper, but sneaking right up to one is a gamble
+                    let disturbance = match (heard, spotted) {
+                        (Some(noise_pos), _) => Some((noise_pos, 10)),
+                        (None, Some((_, target_pos, distance, sneak))) if distance < 1.5 => Some((target_pos, 15 + sneak)),
+                        _ => None
+                    };
+
+                    if let Some((source, difficulty)) = disturbance {
+                        if rng.roll_dice(1, 20) + awareness.perception >= difficulty {
+                            awareness.last_seen = Some(source);
+                            awareness.set_state(AwarenessState::Suspicious);
+                            if in_sight { log.entries.push(format!("The {} wakes up.", name)); }
+                        }
+                    }
+                }
+                AwarenessState::Unaware | AwarenessState::Suspicious | AwarenessState::LostTrack => {
+                    if let Some((target, target_pos, distance, sneak)) = spotted {
+                        let alert_bonus = if awareness.state == AwarenessState::Unaware { 0 } else { 5 };
+                        let roll = rng.roll_dice(1, 20) + awareness.perception + alert_bonus + (viewshed.range - distance as i32);
+                        awareness.last_seen = Some(target_pos);
+                        if roll >= 10 + sneak {
+                            awareness.set_state(AwarenessState::Hunting);
+                            if target == active_entity.target && in_sight {
+                                log.entries.push(format!("The {} notices you!", name));
+                            }
+                        } else {
+                            awareness.set_state(AwarenessState::Suspicious);
+                        }
+                    } else if let Some(noise_pos) = heard {
+                        awareness.last_seen = Some(noise_pos);
+                        if awareness.state == AwarenessState::Unaware {
+                            awareness.set_state(AwarenessState::Suspicious);
+                        }
+                    } else {
+                        let reached = awareness.last_seen.is_none_or(|p| p == my_pos);
+                        let gave_up = match awareness.state {
+                            AwarenessState::Suspicious => awareness.turns_in_state > 5,
+                            AwarenessState::LostTrack => reached || awareness.turns_in_state > 10,
+                            _ => false
+                        };
+                        if gave_up {
+                            awareness.set_state(AwarenessState::Unaware);
+                            awareness.last_seen = None;
+                        }
+                    }
+                }
+                AwarenessState::Hunting => {
+                    match spotted {
+                        Some((_, target_pos, _, _)) => awareness.last_seen = Some(target_pos),
+                        None => awareness.set_state(AwarenessState::LostTrack)
+                    }
+                }
+            }
+
+            awareness.turns_in_state += 1;
+        }
+
+        // Pack members share where they last saw their prey
+        let mut sightings : HashMap<Entity, Point> = HashMap::new();
+        for (entity, group, awareness) in (&entities, &groups, &awarenesses).join() {
+            if entity == active_entity.target || awareness.state != AwarenessState::Hunting { continue; }
+            if let Some(seen) = awareness.last_seen {
+                sightings.insert(group.leader, seen);
+            }
+        }
+        for (entity, group, awareness) in (&entities, &groups, &mut awarenesses).join() {
+            if entity == active_entity.target || matches!(awareness.state, AwarenessState::Asleep | AwarenessState::Hunting) { continue; }
+            if let Some(seen) = sightings.get(&group.leader) {
+                awareness.last_seen = Some(*seen);
+                awareness.set_state(AwarenessState::Hunting);
+            }
+        }
+
+        noise.requests.clear();
+    }
+}
+
+struct NoiseRequest {
+    x: i32,
+    y: i32,
+    volume: i32
+}
+
+pub struct NoiseBuilder {
+    requests: Vec<NoiseRequest>
+}
+
+impl NoiseBuilder {
+    pub fn new() -> NoiseBuilder {
+        NoiseBuilder{ requests: Vec::new() }
+    }
+
+    pub fn request(&mut self, x: i32, y: i32, volume: i32) {
+        self.requests.push(
+            NoiseRequest { x, y, volume }
+        )
+    }
+}
@@ -1,36 +1,142 @@
 use specs::prelude::*;
+use bracket_lib::prelude::*;
 
-use crate::components::{Damage, PoolStats};
+use crate::{game::{GameLog, corpse, drop_loot, gold_pile}, map::Map, components::*};
 
 pub struct DamageSystem {}
 
 impl<'a> System<'a> for DamageSystem {
-    type SystemData = ( WriteStorage<'a, PoolStats>,
-                        WriteStorage<'a, Damage> );
+    type SystemData = ( Entities<'a>,
+                        WriteStorage<'a, PoolStats>,
+                        WriteStorage<'a, Damage>,
+                        ReadStorage<'a, Resistances>,
+                        WriteStorage<'a, Dead>,
+                        ReadExpect<'a, Entity>,
+                        WriteExpect<'a, GameLog> );
 
     fn run(&mut self, data : Self::SystemData) {
-        let (mut stats, mut damage) = data;
+        let (entities, mut stats, mut damage, resistances, mut dead, player_entity, mut log) = data;
 
-        for (mut stats, damage) in (&mut stats, &damage).join() {
-            stats.hp.current -= damage.amount.iter().sum::<i32>();
+        for (entity, stats, damage, resistances) in (&entities, &mut stats, &damage, resistances.maybe()).join() {
+            stats.hp.current -= damage.amount.iter()
+                .map(|(amount, kind)| resisted_damage(resistances, *amount, *kind))
+                .sum::<i32>();
+
+            if stats.hp.current < 1 && dead.get(entity).is_none() {
+                let cause = damage.amount.last().map_or(DamageType::Physical, |(_, kind)| *kind);
+                dead.insert(entity, Dead{ killer: damage.source, cause }).expect("Unable to insert Dead");
+                if entity == *player_entity {
+                    log.entries.push("You have died!".to_string());
+                }
+            }
         }
 
         damage.clear();
     }
 }
 
-pub fn delete_the_dead(ecs : &mut World) {
-    let mut dead : Vec<Entity> = Vec::new();
+pub fn xp_reward(level: i32) -> i32 {
+    level * 10
+}
+
+// Clears out everything that died this turn, returns true once the hero is among them
+pub fn delete_the_dead(ecs : &mut World) -> bool {
+    let mut dead : Vec<(Entity, Option<Entity>)> = Vec::new();
     // Using a scope to make the borrow checker happy
     {
-        let combat_stats = ecs.read_storage::<PoolStats>();
+        let deaths = ecs.read_storage::<Dead>();
         let entities = ecs.entities();
-        for (entity, stats) in (&entities, &combat_stats).join() {
-            if stats.hp.current < 1 { dead.push(entity); }
+        for (entity, death) in (&entities, &deaths).join() {
+            dead.push((entity, death.killer));
         }
     }
 
-    for victim in dead {
+    let player_entity = *ecs.fetch::<Entity>();
+    let depth = ecs.fetch::<Map>().depth;
+    let mut game_over = false;
+
+    for (victim, killer) in dead {
+        // The hero stays around for the recap, their death was logged when it happened
+        if victim == player_entity {
+            game_over = true;
+            continue;
+        }
+
+        let mut remains : Option<(Point, String, i32)> = None;
+        {
+            let names = ecs.read_storage::<Name>();
+            let positions = ecs.read_storage::<Position>();
+            let mut pool_stats = ecs.write_storage::<PoolStats>();
+            let mut active_entity = ecs.fetch_mut::<ActiveEntity>();
+            let mut viewsheds = ecs.write_storage::<Viewshed>();
+            let mut log = ecs.fetch_mut::<GameLog>();
+
+            let victim_name = names.get(victim).map_or("Something".to_string(), |n| n.name.clone());
+            match killer.and_then(|k| names.get(k)) {
+                Some(killer_name) if killer != Some(victim) => log.entries.push(format!("{} is slain by {}.", victim_name, killer_name.name)),
+                _ => log.entries.push(format!("{} dies.", victim_name))
+            }
+
+            // Whatever the hero's borrowed body kills still counts for the hero
+            // The killer pockets the purse, only coins nobody claims end up on the floor
+            let rewarded = killer.map(|k| if k == active_entity.target { player_entity } else { k });
+            let (level, mut gold) = pool_stats.get(victim).map_or((0, 0), |p| (p.level, p.gold));
+            if let Some(rewarded) = rewarded.filter(|r| *r != victim) {
+                if let Some(stats) = pool_stats.get_mut(rewarded) {
+                    stats.xp += xp_reward(level);
+                    stats.gold += gold;
+                    if rewarded == player_entity {
+                        log.entries.push(if gold > 0 {
+                            format!("You gain {} xp and {} gold.", xp_reward(level), gold)
+                        } else {
+                            format!("You gain {} xp.", xp_reward(level))
+                        });
+                    }
+                    gold = 0;
+                }
+            }
+
+            // Losing the body we were riding snaps us back into the hero
+            if victim == active_entity.target {
+                active_entity.target = player_entity;
+                if let Some(viewshed) = viewsheds.get_mut(player_entity) {
+                    viewshed.dirty = true;
+                }
+                if let Some(pos) = positions.get(player_entity) {
+                    *ecs.write_resource::<Point>() = Point::new(pos.x, pos.y);
+                }
+                log.entries.push("Your borrowed body falls, and you snap back into your own.".to_string());
+            }
+
+            if let Some(pos) = positions.get(victim) {
+                remains = Some((Point::new(pos.x, pos.y), victim_name, gold));
+            }
+        }
+
+        if let Some((pos, victim_name, gold)) = remains {
+            // Whatever it carried spills out onto the floor
+            let carried : Vec<Entity> = {
+                let entities = ecs.entities();
+                let items_owned = ecs.read_storage::<ItemOwned>();
+                (&entities, &items_owned).join().filter(|(_, owned)| owned.owner == victim).map(|(e, _)| e).collect()
+            };
+            for item in carried {
+                ecs.write_storage::<ItemOwned>().remove(item);
+                ecs.write_storage::<Equipped>().remove(item);
+                ecs.write_storage::<Position>().insert(item, Position{ x: pos.x, y: pos.y }).expect("Unable to insert Position");
+            }
+
+            if ecs.read_storage::<Mob>().get(victim).is_some() {
+                corpse(ecs, pos.x, pos.y, &victim_name);
+                drop_loot(ecs, pos.x, pos.y, depth);
+                if gold > 0 {
+                    gold_pile(ecs, pos.x, pos.y, gold);
+                }
+            }
+        }
+
         ecs.delete_entity(victim).expect("Unable to delete");
     }
+
+    game_over
 }
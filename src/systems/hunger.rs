@@ -0,0 +1,49 @@
+use specs::prelude::*;
+
+use crate::{RunState, game::GameLog, components::*};
+
+pub struct HungerSystem {}
+
+impl<'a> System<'a> for HungerSystem {
+    type SystemData = ( ReadExpect<'a, RunState>,
+                        ReadExpect<'a, Entity>,
+                        WriteExpect<'a, GameLog>,
+                        Entities<'a>,
+                        WriteStorage<'a, HungerClock>,
+                        WriteStorage<'a, Damage>);
+
+    fn run(&mut self, data : Self::SystemData) {
+        let (runstate, player_entity, mut log, entities, mut hunger, mut inflicted_damage) = data;
+
+        // The belly empties once per turn, like any other condition
+        if *runstate != RunState::MonsterTurn { return; }
+
+        for (entity, clock) in (&entities, &mut hunger).join() {
+            if clock.state == HungerState::Starving {
+                Damage::new(&mut inflicted_damage, entity, 1, DamageType::Physical, None);
+                if entity == *player_entity {
+                    log.entries.push("Your hunger pangs are getting painful, for 1 hp.".to_string());
+                }
+                continue;
+            }
+
+            clock.duration -= 1;
+            if clock.duration > 0 { continue; }
+
+            clock.state = match clock.state {
+                HungerState::WellFed => HungerState::Normal,
+                HungerState::Normal => HungerState::Hungry,
+                HungerState::Hungry | HungerState::Starving => HungerState::Starving
+            };
+            clock.duration = clock.state.duration();
+            if entity == *player_entity {
+                match clock.state {
+                    HungerState::Normal => log.entries.push("You are no longer well fed.".to_string()),
+                    HungerState::Hungry => log.entries.push("You are hungry.".to_string()),
+                    HungerState::Starving => log.entries.push("You are starving!".to_string()),
+                    HungerState::WellFed => {}
+                }
+            }
+        }
+    }
+}
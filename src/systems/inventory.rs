@@ -1,25 +1,54 @@
 
 use specs::prelude::*;
 
-use crate::{game::GameLog, components::*, map::Map};
+use bracket_lib::prelude::*;
+
+use crate::{game::{GameLog, Identification}, components::*, map::Map};
+
+use super::ParticleBuilder;
 
 pub struct ItemPickupSystem{}
 
 impl<'a> System<'a> for ItemPickupSystem {
+    #[allow(clippy::type_complexity)]
     type SystemData = ( ReadExpect<'a, ActiveEntity>,
+                        ReadExpect<'a, Entity>,
                         WriteExpect<'a, GameLog>,
+                        Entities<'a>,
                         WriteStorage<'a, PickupItemIntent>,
                         WriteStorage<'a, Position>,
                         ReadStorage<'a, Name>,
                         WriteStorage<'a, ItemOwned>,
+                        ReadStorage<'a, Gold>,
+                        WriteStorage<'a, PoolStats>,
+                        WriteStorage<'a, Stackable>
                       );
     
     fn run(&mut self, data: Self::SystemData) {
-        let (active_entity, mut log, mut pickup_intent, mut pos, names, mut item_owned) = data;
+        let (active_entity, player_entity, mut log, entities, mut pickup_intent, mut pos, names, mut item_owned, gold, mut pool_stats, mut stacks) = data;
 
         for pickup in pickup_intent.join() {
+            // Coins go straight to the hero's purse, whoever scoops them up
+            if let Some(gold) = gold.get(pickup.item) {
+                if let Some(stats) = pool_stats.get_mut(*player_entity) {
+                    stats.gold += gold.amount;
+                }
+                log.entries.push(format!("You pick up {} gold.", gold.amount));
+                entities.delete(pickup.item).expect("Unable to delete gold");
+                continue;
+            }
+
             pos.remove(pickup.item);
-            item_owned.insert(pickup.item, ItemOwned{ owner: pickup.picked_by }).expect("Unable to insert ItemOwned");
+            // Identical items fold into the stack already in the pack
+            if let Some(stack) = matching_stack(&entities, pickup.item, pickup.picked_by, &item_owned, &names, &stacks) {
+                let quantity = stacks.get(pickup.item).map_or(1, |s| s.quantity);
+                if let Some(stack) = stacks.get_mut(stack) {
+                    stack.quantity += quantity;
+                }
+                entities.delete(pickup.item).expect("Unable to delete item");
+            } else {
+                item_owned.insert(pickup.item, ItemOwned{ owner: pickup.picked_by }).expect("Unable to insert ItemOwned");
+            }
 
             if pickup.picked_by == active_entity.target {
                 log.entries.push(format!("You pick up the {}.", names.get(pickup.item).unwrap().name));
@@ -30,37 +59,318 @@ impl<'a> System<'a> for ItemPickupSystem {
     }
 }
 
+pub struct ItemDropSystem{}
+
+impl<'a> System<'a> for ItemDropSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = ( ReadExpect<'a, ActiveEntity>,
+                        WriteExpect<'a, GameLog>,
+                        Entities<'a>,
+                        WriteStorage<'a, DropItemIntent>,
+                        ReadStorage<'a, Name>,
+                        WriteStorage<'a, Position>,
+                        WriteStorage<'a, ItemOwned>,
+                        WriteStorage<'a, Equipped>,
+                        ReadStorage<'a, CursedItem>,
+                        WriteStorage<'a, RecalculateStats>
+                      );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (active_entity, mut log, entities, mut drop_intent, names, mut positions, mut items_owned, mut equipped, cursed, mut recalculate) = data;
+
+        for (entity, intent) in (&entities, &drop_intent).join() {
+            let item_name = names.get(intent.item).map_or("item", |n| n.name.as_str());
+            let is_active = entity == active_entity.target;
+
+            if equipped.get(intent.item).is_some() {
+                if cursed.get(intent.item).is_some() {
+                    if is_active {
+                        log.entries.push(format!("The {} is cursed, it will not come off!", item_name));
+                    }
+                    continue;
+                }
+                equipped.remove(intent.item);
+                recalculate.insert(entity, RecalculateStats{}).expect("Unable to insert RecalculateStats");
+            }
+
+            let dropper_pos = match positions.get(entity) {
+                None => continue,
+                Some(pos) => Position{ x: pos.x, y: pos.y }
+            };
+            items_owned.remove(intent.item);
+            positions.insert(intent.item, dropper_pos).expect("Unable to insert Position");
+            if is_active {
+                log.entries.push(format!("You drop the {}.", item_name));
+            }
+        }
+
+        drop_intent.clear();
+    }
+}
+
+pub struct ItemEquipSystem{}
+
+impl<'a> System<'a> for ItemEquipSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = ( ReadExpect<'a, ActiveEntity>,
+                        WriteExpect<'a, GameLog>,
+                        Entities<'a>,
+                        WriteStorage<'a, EquipItemIntent>,
+                        ReadStorage<'a, Name>,
+                        ReadStorage<'a, Equippable>,
+                        WriteStorage<'a, Equipped>,
+                        WriteStorage<'a, RecalculateStats>,
+                        ReadStorage<'a, CursedItem>
+                      );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (active_entity, mut log, entities, mut equip_intent, names, equippable, mut equipped, mut recalculate, cursed) = data;
+
+        for (entity, intent) in (&entities, &equip_intent).join() {
+            let item_name = names.get(intent.item).map_or("item", |n| n.name.as_str());
+            let is_active = entity == active_entity.target;
+
+            let stuck = |item: Entity| equipped.get(item).is_some() && cursed.get(item).is_some();
+
+            // Equipping what is already worn takes it off
+            if stuck(intent.item) {
+                if is_active {
+                    log.entries.push(format!("The {} is cursed, it will not come off!", item_name));
+                }
+                continue;
+            } else if equipped.get(intent.item).is_some() {
+                equipped.remove(intent.item);
+                if is_active {
+                    log.entries.push(format!("You unequip the {}.", item_name));
+                }
+            } else if let Some(equippable) = equippable.get(intent.item) {
+                let replaced : Vec<Entity> = (&entities, &equipped).join()
+                    .filter(|(_, worn)| worn.owner == entity && worn.slot == equippable.slot)
+                    .map(|(item, _)| item)
+                    .collect();
+                if let Some(item) = replaced.iter().find(|item| stuck(**item)) {
+                    if is_active {
+                        log.entries.push(format!("The {} is cursed, it will not come off!", names.get(*item).map_or("item", |n| n.name.as_str())));
+                    }
+                    continue;
+                }
+                for item in replaced {
+                    equipped.remove(item);
+                    if is_active {
+                        log.entries.push(format!("You unequip the {}.", names.get(item).map_or("item", |n| n.name.as_str())));
+                    }
+                }
+
+                equipped.insert(intent.item, Equipped{ owner: entity, slot: equippable.slot }).expect("Unable to insert Equipped");
+                if is_active {
+                    log.entries.push(format!("You equip the {} on your {}.", item_name, equippable.slot.name()));
+                    if cursed.get(intent.item).is_some() {
+                        log.entries.push(format!("The {} is cursed! It clings to you.", item_name));
+                    }
+                }
+            }
+
+            recalculate.insert(entity, RecalculateStats{}).expect("Unable to insert RecalculateStats");
+        }
+
+        equip_intent.clear();
+    }
+}
+
 pub struct ItemUseSystem{}
 
 impl<'a> System<'a> for ItemUseSystem {
+    #[allow(clippy::type_complexity)]
     type SystemData = ( ReadExpect<'a, ActiveEntity>,
                         WriteExpect<'a, GameLog>,
-                        ReadExpect<'a, Map>,
+                        WriteExpect<'a, Map>,
                         Entities<'a>,
                         WriteStorage<'a, UseItemIntent>,
                         ReadStorage<'a, Name>,
                         ReadStorage<'a, Heals>,
                         WriteStorage<'a, PoolStats>,
-                        ReadStorage<'a, Consumable>
+                        WriteStorage<'a, Consumable>,
+                        ReadStorage<'a, InflictsDamage>,
+                        WriteStorage<'a, Damage>,
+                        WriteExpect<'a, ParticleBuilder>,
+                        ReadStorage<'a, AreaOfEffect>,
+                        ReadStorage<'a, InflictsCondition>,
+                        WriteStorage<'a, StatusEffect>,
+                        ReadStorage<'a, RemovesCurse>,
+                        WriteStorage<'a, CursedItem>,
+                        ReadStorage<'a, ItemOwned>,
+                        WriteStorage<'a, Stackable>,
+                        WriteExpect<'a, RandomNumberGenerator>,
+                        WriteStorage<'a, Dead>,
+                        ReadStorage<'a, Resistances>,
+                        // SystemData tuples top out at 26 entries, so related storages travel together
+                        (WriteExpect<'a, Identification>, ReadStorage<'a, Unidentified>, ReadStorage<'a, Identifies>),
+                        (ReadStorage<'a, MagicMapper>, ReadStorage<'a, TeleportSelf>, ReadStorage<'a, ProvidesFood>, WriteStorage<'a, HungerClock>),
+                        (WriteStorage<'a, Position>, WriteStorage<'a, Viewshed>, WriteExpect<'a, Point>)
                       );
 
     fn run(&mut self, data : Self::SystemData) {
-        let (player_entity, mut gamelog, _map, entities, mut use_intent, names, healing, mut pool_stats, consumables) = data;
+        let (player_entity, mut gamelog, mut map, entities, mut use_intent, names, healing, mut pool_stats, mut consumables, inflicts_damage, mut inflicted_damage, mut particle_builder, aoe, inflicts_condition, mut status_effects, removes_curse, mut cursed, items_owned, mut stacks, mut rng, mut dead, resistances, (mut identification, unidentified, identifies), (magic_mappers, teleporters, food, mut hunger), (mut positions, mut viewsheds, mut camera)) = data;
 
         for (entity, useitem) in (&entities, &use_intent).join() {
             let mut used_item = true;
 
             let mut targets : Vec<Entity> = Vec::new();
             match useitem.target {
-                None => {targets.push(player_entity.target)}
-                Some(_target) => {}
+                None => {targets.push(entity)}
+                Some(target) => {
+                    match aoe.get(useitem.item) {
+                        None => {
+                            let idx = map.xy_idx(target.x, target.y);
+                            for mob in map.tile_content[idx].iter() {
+                                if pool_stats.get(*mob).is_some() {
+                                    targets.push(*mob);
+                                }
+                            }
+                        }
+                        Some(area) => {
+                            // The blast only reaches what the centre of it can see
+                            let mut blast_tiles = field_of_view(target, area.radius, &*map);
+                            blast_tiles.retain(|p| p.x > 0 && p.x < map.width - 1 && p.y > 0 && p.y < map.height - 1);
+                            for tile in blast_tiles.iter() {
+                                let idx = map.xy_idx(tile.x, tile.y);
+                                for mob in map.tile_content[idx].iter() {
+                                    if pool_stats.get(*mob).is_some() {
+                                        targets.push(*mob);
+                                    }
+                                }
+                                particle_builder.request(tile.x, tile.y, RGB::named(ORANGE), RGB::named(BLACK), to_cp437('░'), 200.0);
+                            }
+                        }
+                    }
+                }
+            }
+
+            // A mob using itself is an ability, and it does not get caught in its own blast
+            if useitem.item == entity {
+                targets.retain(|target| *target != entity);
+                if let Some(name) = names.get(entity) {
+                    gamelog.entries.push(format!("The {} unleashes a blast!", name.name));
+                }
+            }
+
+            let item_damages = inflicts_damage.get(useitem.item);
+            if let Some(damage) = item_damages {
+                used_item = true;
+                for target in targets.iter() {
+                    Damage::new(&mut inflicted_damage, *target, damage.amount, damage.kind, Some(entity));
+                    let target_name = names.get(*target).map_or("something", |n| n.name.as_str());
+                    let taken = resisted_damage(resistances.get(*target), damage.amount, damage.kind);
+                    if entity == player_entity.target {
+                        gamelog.entries.push(format!("You use the {} on {}, inflicting {} hp.", names.get(useitem.item).unwrap().name, target_name, taken));
+                    } else if useitem.item == entity {
+                        gamelog.entries.push(format!("{} is caught in it, for {} hp.", target_name, taken));
+                    }
+                }
+                if let (Some(target), None) = (useitem.target, aoe.get(useitem.item)) {
+                    particle_builder.request(target.x, target.y, RGB::named(RED), RGB::named(BLACK), to_cp437('‼'), 200.0);
+                }
+            }
+
+            if let Some(inflicts) = inflicts_condition.get(useitem.item) {
+                used_item = true;
+                for target in targets.iter() {
+                    StatusEffect::apply(&entities, &mut status_effects, *target, inflicts.condition, inflicts.turns, inflicts.potency);
+                    if let Some(name) = names.get(*target) {
+                        gamelog.entries.push(format!("{} is {}!", name.name, inflicts.condition.name()));
+                    }
+                }
+            }
+
+            // Lifts the curse off everything the user carries
+            if removes_curse.get(useitem.item).is_some() {
+                used_item = true;
+                let lifted : Vec<Entity> = (&entities, &items_owned, &cursed).join()
+                    .filter(|(_, owned, _)| owned.owner == entity)
+                    .map(|(item, _, _)| item)
+                    .collect();
+                for item in lifted.iter() {
+                    cursed.remove(*item);
+                }
+                if entity == player_entity.target {
+                    gamelog.entries.push(if lifted.is_empty() { "You feel a brief warmth, then nothing.".to_string() } else { "You feel the weight of a curse lift.".to_string() });
+                }
+            }
+
+            // Reveals one mystery the reader carries
+            if identifies.get(useitem.item).is_some() {
+                used_item = true;
+                let mysteries : Vec<(Entity, &Unidentified)> = (&entities, &items_owned, &unidentified).join()
+                    .filter(|(item, owned, u)| owned.owner == entity && *item != useitem.item && !identification.is_known(&u.name))
+                    .map(|(item, _, u)| (item, u))
+                    .collect();
+                if entity == player_entity.target {
+                    if mysteries.is_empty() {
+                        gamelog.entries.push("You learn nothing new.".to_string());
+                    } else {
+                        let (item, mystery) = mysteries[rng.range(0, mysteries.len() as i32) as usize];
+                        identification.identify(&mystery.name);
+                        gamelog.entries.push(format!("The {} is revealed: {}.", names.get(item).map_or("item", |n| n.name.as_str()), mystery.name));
+                    }
+                }
+            }
+
+            // The whole level floods into the reader's mind
+            if magic_mappers.get(useitem.item).is_some() {
+                used_item = true;
+                for tile in map.revealed_tiles.iter_mut() {
+                    *tile = true;
+                }
+                if entity == player_entity.target {
+                    gamelog.entries.push("The layout of the level floods into your mind!".to_string());
+                }
+            }
+
+            // Whisks the user off to a random open tile
+            if teleporters.get(useitem.item).is_some() {
+                used_item = true;
+                let landing = random_open_tile(&map, &mut rng);
+                if let (Some(landing), Some(pos)) = (landing, positions.get_mut(entity)) {
+                    let from_idx = map.xy_idx(pos.x, pos.y);
+                    map.blocked[from_idx] = false;
+                    map.blocked[landing] = true;
+                    pos.x = landing as i32 % map.width;
+                    pos.y = landing as i32 / map.width;
+                    if let Some(viewshed) = viewsheds.get_mut(entity) {
+                        viewshed.dirty = true;
+                    }
+                    if entity == player_entity.target {
+                        camera.x = pos.x;
+                        camera.y = pos.y;
+                        gamelog.entries.push("The world lurches, and you are somewhere else.".to_string());
+                    }
+                }
+            }
+
+            if food.get(useitem.item).is_some() {
+                used_item = true;
+                let item_name = names.get(useitem.item).map_or("food", |n| n.name.as_str());
+                match hunger.get_mut(entity) {
+                    Some(clock) => {
+                        *clock = HungerClock::new(HungerState::WellFed);
+                        if entity == player_entity.target {
+                            gamelog.entries.push(format!("You eat the {}, and feel well fed.", item_name));
+                        }
+                    }
+                    None => {
+                        if entity == player_entity.target {
+                            gamelog.entries.push(format!("You eat the {}, but this body has no use for it.", item_name));
+                        }
+                    }
+                }
             }
 
             let item_heals = healing.get(useitem.item);
             match item_heals {
                 None => {}
                 Some(healer) => {
-                    used_item = false;
+                    // Healing nobody only wastes it when it was aimed somewhere
+                    used_item = useitem.target.is_some();
                     for target in targets.iter() {
                         let stats = pool_stats.get_mut(*target);
                         if let Some(stats) = stats {
@@ -74,12 +384,43 @@ impl<'a> System<'a> for ItemUseSystem {
                 }
             }
 
+            // Using a mystery item gives its nature away
+            if used_item && entity == player_entity.target {
+                if let Some(mystery) = unidentified.get(useitem.item) {
+                    if identification.identify(&mystery.name) {
+                        gamelog.entries.push(format!("You now recognise the {}.", mystery.name));
+                    }
+                }
+            }
+
             if used_item {
-                let consumable = consumables.get(useitem.item);
+                let consumable = consumables.get_mut(useitem.item);
                 match consumable {
                     None => {}
-                    Some(_) => {
-                        entities.delete(useitem.item).expect("Delete failed");
+                    Some(consumable) => {
+                        // Only gone once the last charge is spent, and the next one in the stack takes over
+                        consumable.charges -= 1;
+                        if consumable.charges <= 0 {
+                            if let Some(stack) = stacks.get_mut(useitem.item).filter(|s| s.quantity > 1) {
+                                stack.quantity -= 1;
+                                consumable.charges = consumable.max_charges;
+                                continue;
+                            }
+                            // A spent ability takes the mob with it, through the usual death so it leaves its remains
+                            if useitem.item == entity {
+                                let provoker = useitem.target.and_then(|target| {
+                                    let idx = map.xy_idx(target.x, target.y);
+                                    map.tile_content[idx].iter().find(|e| **e != entity && pool_stats.get(**e).is_some()).copied()
+                                });
+                                let cause = inflicts_damage.get(entity).map_or(DamageType::Physical, |d| d.kind);
+                                dead.insert(entity, Dead{ killer: provoker, cause }).expect("Unable to insert Dead");
+                                continue;
+                            }
+                            if consumable.max_charges > 1 && entity == player_entity.target {
+                                gamelog.entries.push(format!("The {} is spent.", names.get(useitem.item).map_or("item", |n| n.name.as_str())));
+                            }
+                            entities.delete(useitem.item).expect("Delete failed");
+                        }
                     }
                 }
             }
@@ -87,4 +428,35 @@ impl<'a> System<'a> for ItemUseSystem {
 
         use_intent.clear();
     }
-}
\ No newline at end of file
+}
+
+// A handful of tries at finding somewhere to land, walls and occupied tiles are out
+fn random_open_tile(map: &Map, rng: &mut RandomNumberGenerator) -> Option<usize> {
+    (0 .. 100)
+        .map(|_| map.xy_idx(rng.range(1, map.width - 1), rng.range(1, map.height - 1)))
+        .find(|idx| !map.blocked[*idx])
+}
+
+// Anything the hero has worked out drops its disguise, wherever it lies
+pub struct ItemIdentificationSystem{}
+
+impl<'a> System<'a> for ItemIdentificationSystem {
+    type SystemData = ( ReadExpect<'a, Identification>,
+                        Entities<'a>,
+                        WriteStorage<'a, Unidentified>,
+                        WriteStorage<'a, Name>
+                      );
+
+    fn run(&mut self, data : Self::SystemData) {
+        let (identification, entities, mut unidentified, mut names) = data;
+
+        let revealed : Vec<(Entity, String)> = (&entities, &unidentified).join()
+            .filter(|(_, u)| identification.is_known(&u.name))
+            .map(|(item, u)| (item, u.name.clone()))
+            .collect();
+        for (item, name) in revealed {
+            names.insert(item, Name{ name }).expect("Unable to insert Name");
+            unidentified.remove(item);
+        }
+    }
+}
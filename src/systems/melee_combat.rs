@@ -2,7 +2,9 @@ use specs::prelude::*;
 use bracket_lib::prelude::*;
 use crate::{game::GameLog, components::Position};
 
-use super::super::{CombatStats, PoolStats, MeleeIntent, Name, Damage, ParticleBuilder};
+use super::super::{CombatStats, PoolStats, MeleeIntent, Name, Damage, DamageType, ParticleBuilder, Awareness, AwarenessState, InflictsCondition, StatusEffect,
+    Equipped, MeleeWeapon, Wearable, Enchantment, Durability, Resistances, melee_power, armor, resisted_damage};
+use super::NoiseBuilder;
 
 pub struct MeleeCombatSystem {}
 
@@ -16,13 +18,25 @@ impl <'a> System<'a> for MeleeCombatSystem {
         WriteStorage<'a, Damage>,
         WriteExpect<'a, GameLog>,
         WriteExpect<'a, ParticleBuilder>,
-        ReadStorage<'a, Position>
+        ReadStorage<'a, Position>,
+        WriteStorage<'a, Awareness>,
+        WriteExpect<'a, NoiseBuilder>,
+        ReadStorage<'a, InflictsCondition>,
+        WriteStorage<'a, StatusEffect>,
+        WriteExpect<'a, RandomNumberGenerator>,
+        ReadStorage<'a, Equipped>,
+        ReadStorage<'a, MeleeWeapon>,
+        ReadStorage<'a, Wearable>,
+        ReadStorage<'a, Enchantment>,
+        WriteStorage<'a, Durability>,
+        ReadStorage<'a, Resistances>
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (entities, mut melee_intent, names, combat_stats, pool_stats, mut inflicted_damage, mut log, mut particle_builder, positions) = data;
+        let (entities, mut melee_intent, names, combat_stats, pool_stats, mut inflicted_damage, mut log, mut particle_builder, positions, mut awareness, mut noise, inflicts_condition, mut status_effects, mut rng, equipped, melee_weapons, wearables, enchantments, mut durability, resistances) = data;
         
-        for (_entity, intent, name, stats, pool) in (&entities, &melee_intent, &names, &combat_stats, &pool_stats).join() {
+        let mut worn_down : Vec<Entity> = Vec::new();
+        for (entity, intent, name, stats, pool) in (&entities, &melee_intent, &names, &combat_stats, &pool_stats).join() {
             if pool.hp.current > 0 {
                 let target_combat_stats = combat_stats.get(intent.target).unwrap();
                 let target_pool_stats = pool_stats.get(intent.target).unwrap();
@@ -30,20 +44,93 @@ impl <'a> System<'a> for MeleeCombatSystem {
                     let target_name = names.get(intent.target).unwrap();
                     let target_pos = positions.get(intent.target);
                     if let Some(pos) = target_pos {
-                        particle_builder.request(pos.x, pos.y, RGB::named(ORANGE), RGB::named(BLACK), to_cp437('‼'), 200.0)
+                        particle_builder.request(pos.x, pos.y, RGB::named(ORANGE), RGB::named(BLACK), to_cp437('‼'), 200.0);
+                        noise.request(pos.x, pos.y, 8);
                     }
-                    let damage = i32::max(0, stats.attack - target_combat_stats.defense);
+                    // Catching someone asleep or unaware hurts twice as much, and they get no chance to dodge
+                    let mut ambush = false;
+                    if let Some(target_awareness) = awareness.get_mut(intent.target) {
+                        if matches!(target_awareness.state, AwarenessState::Asleep | AwarenessState::Unaware) {
+                            ambush = true;
+                            log.entries.push(format!("{} ambushes {}!", &name.name, &target_name.name));
+                        }
+                        target_awareness.set_state(AwarenessState::Hunting);
+                        target_awareness.last_seen = positions.get(entity).map(|p| Point::new(p.x, p.y));
+                    }
+
+                    let evade = if ambush { 0 } else { target_combat_stats.evade };
+                    let attack = stats.attack + melee_power(entity, &equipped, &melee_weapons, &enchantments, &durability);
+                    let defense = target_combat_stats.defense + armor(intent.target, &equipped, &wearables, &enchantments, &durability);
+                    let resisted = |damage| resisted_damage(resistances.get(intent.target), damage, DamageType::Physical);
+                    let damage = match roll_attack(&mut rng, attack, 0, defense, evade) {
+                        AttackRoll::Fumble => {
+                            log.entries.push(format!("{} fumbles the attack!", &name.name));
+                            // A botched swing nicks the blade
+                            worn_down.extend((&entities, &equipped, &melee_weapons).join().filter(|(_, e, _)| e.owner == entity).map(|(item, _, _)| item));
+                            0
+                        }
+                        AttackRoll::Miss => {
+                            log.entries.push(format!("{} misses {}.", &name.name, &target_name.name));
+                            0
+                        }
+                        AttackRoll::Hit(damage) => {
+                            let damage = if ambush { damage * 2 } else { damage };
+                            log.entries.push(format!("{} hits {}, for {} hp.", &name.name, &target_name.name, resisted(damage)));
+                            damage
+                        }
+                        AttackRoll::Critical(damage) => {
+                            let damage = if ambush { damage * 2 } else { damage };
+                            // A critical blow dents whatever armor took it
+                            worn_down.extend((&entities, &equipped, &wearables).join().filter(|(_, e, _)| e.owner == intent.target).map(|(item, _, _)| item));
+                            log.entries.push(format!("{} hits {}, for {} hp. Critical!", &name.name, &target_name.name, resisted(damage)));
+                            damage
+                        }
+                    };
 
-                    if damage == 0 {
-                        log.entries.push(format!("{} is unable to hurt {}", &name.name, &target_name.name));
-                    } else {
-                        log.entries.push(format!("{} hits {}, for {} hp.", &name.name, &target_name.name, damage));
-                        Damage::new(&mut inflicted_damage, intent.target, damage);
+                    if damage > 0 {
+                        Damage::new(&mut inflicted_damage, intent.target, damage, DamageType::Physical, Some(entity));
+
+                        if let Some(inflicts) = inflicts_condition.get(entity) {
+                            StatusEffect::apply(&entities, &mut status_effects, intent.target, inflicts.condition, inflicts.turns, inflicts.potency);
+                            log.entries.push(format!("{} is {}!", &target_name.name, inflicts.condition.name()));
+                        }
                     }
                 }
             }
         }
 
+        for item in worn_down {
+            if let Some(durability) = durability.get_mut(item).filter(|d| d.current > 0) {
+                durability.current -= 1;
+                if durability.current == 0 {
+                    log.entries.push(format!("The {} breaks!", names.get(item).map_or("gear", |n| n.name.as_str())));
+                }
+            }
+        }
+
         melee_intent.clear();
     }
-}
\ No newline at end of file
+}
+
+pub enum AttackRoll {
+    Fumble, Miss, Hit(i32), Critical(i32)
+}
+
+// A d20 decides the swing: 1 always whiffs, 20 always lands for double, evade makes everything between harder
+pub fn roll_attack(rng: &mut RandomNumberGenerator, attack: i32, to_hit_modifier: i32, defense: i32, evade: i32) -> AttackRoll {
+    let natural = rng.roll_dice(1, 20);
+    if natural == 1 {
+        return AttackRoll::Fumble;
+    }
+    if natural != 20 && natural + attack / 2 + to_hit_modifier < 10 + evade {
+        return AttackRoll::Miss;
+    }
+
+    // Anything that lands hurts a little, however thick the armour
+    let damage = i32::max(1, rng.roll_dice(1, i32::max(1, attack)) + attack / 2 - defense);
+    if natural == 20 {
+        AttackRoll::Critical(damage * 2)
+    } else {
+        AttackRoll::Hit(damage)
+    }
+}
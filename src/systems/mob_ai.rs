@@ -0,0 +1,181 @@
+use std::collections::HashSet;
+
+use specs::prelude::*;
+use bracket_lib::prelude::*;
+
+use crate::{RunState, map::Map, components::*, game::{FactionTable, effective_faction}};
+
+use super::{ranged_weapon, line_of_fire};
+
+pub struct MobAI {}
+
+impl<'a> System<'a> for MobAI {
+    #[allow(clippy::type_complexity)]
+    type SystemData = ( WriteExpect<'a, Map>,
+                        ReadExpect<'a, ActiveEntity>,
+                        ReadExpect<'a, RunState>,
+                        ReadExpect<'a, FactionTable>,
+                        Entities<'a>,
+                        ReadStorage<'a, Mob>,
+                        ReadStorage<'a, Faction>,
+                        ReadStorage<'a, Awareness>,
+                        ReadStorage<'a, GroupMember>,
+                        WriteStorage<'a, Viewshed>,
+                        WriteStorage<'a, Position>,
+                        WriteStorage<'a, MeleeIntent>,
+                        WriteStorage<'a, RangedIntent>,
+                        ReadStorage<'a, RangedWeapon>,
+                        ReadStorage<'a, ItemOwned>,
+                        ReadStorage<'a, Ranged>,
+                        ReadStorage<'a, InflictsDamage>,
+                        WriteStorage<'a, UseItemIntent>,
+                        WriteExpect<'a, RandomNumberGenerator>,
+                        ReadStorage<'a, StatusEffect>,
+                        WriteStorage<'a, EntityMoved>);
+
+    fn run(&mut self, data : Self::SystemData) {
+        let (mut map, active_entity, runstate, faction_table, entities, mobs, factions, awarenesses, groups, mut viewsheds, mut positions, mut melee_intent, mut ranged_intent, ranged_weapons, items_owned, abilities, inflicts_damage, mut use_intent, mut rng, status_effects, mut entity_moved) = data;
+
+        if *runstate != RunState::MonsterTurn { return; }
+
+        // Positions move while we iterate, so work from a snapshot
+        let snapshot : Vec<(Entity, Point)> = (&entities, &positions).join().map(|(e, p)| (e, Point::new(p.x, p.y))).collect();
+        // Tiles around a target that a pack member is already heading for
+        let mut claimed : HashSet<usize> = HashSet::new();
+
+        for (entity, _mob, faction, viewshed, pos) in (&entities, &mobs, &factions, &mut viewsheds, &mut positions).join() {
+            // The curse put the player in this body
+            if entity == active_entity.target || has_condition(entity, Condition::Frozen, &status_effects) { continue; }
+
+            // Sleeping or oblivious mobs stay put until the awareness system says otherwise
+            let awareness = awarenesses.get(entity);
+            if let Some(awareness) = awareness {
+                if matches!(awareness.state, AwarenessState::Asleep | AwarenessState::Unaware) { continue; }
+            }
+
+            // A pack that lost its leader breaks and runs
+            let group = groups.get(entity);
+            let routed = group.is_some_and(|g| !entities.is_alive(g.leader));
+            // Fear sends a mob running from everything it would fight, confusion sends it anywhere
+            let afraid = has_condition(entity, Condition::Afraid, &status_effects);
+            let confused = has_condition(entity, Condition::Confused, &status_effects);
+
+            let my_pos = Point::new(pos.x, pos.y);
+            let mut closest_target : Option<(Entity, Point, f32)> = None;
+            let mut threats : Vec<usize> = Vec::new();
+
+            for (other, other_pos) in snapshot.iter() {
+                if *other == entity || !viewshed.visible_tiles.contains(other_pos) { continue; }
+                let their_faction = effective_faction(*other, &active_entity, &factions);
+                if let Some(their_faction) = their_faction {
+                    let distance = DistanceAlg::Pythagoras.distance2d(my_pos, *other_pos);
+                    match faction_table.reaction(&faction.name, their_faction) {
+                        Reaction::Attack if routed || afraid => threats.push(map.xy_idx(other_pos.x, other_pos.y)),
+                        Reaction::Attack => {
+                            if closest_target.is_none_or(|(_, _, d)| distance < d) {
+                                closest_target = Some((*other, *other_pos, distance));
+                            }
+                        }
+                        Reaction::Flee => threats.push(map.xy_idx(other_pos.x, other_pos.y)),
+                        Reaction::Ignore => {}
+                    }
+                }
+            }
+
+            // Only a hunting mob commits to a target, the rest go look where they last saw something
+            if awareness.is_some_and(|a| a.state != AwarenessState::Hunting) {
+                closest_target = None;
+            }
+            let search_point = awareness.and_then(|a| a.last_seen);
+
+            let my_idx = map.xy_idx(pos.x, pos.y);
+            let mut destination : Option<usize> = None;
+
+            if confused {
+                let stumble = Point::new(pos.x + rng.range(-1, 2), pos.y + rng.range(-1, 2));
+                if stumble.x > 0 && stumble.x < map.width - 1 && stumble.y > 0 && stumble.y < map.height - 1 {
+                    destination = Some(map.xy_idx(stumble.x, stumble.y));
+                }
+            } else if !threats.is_empty() {
+                let flee_map = DijkstraMap::new(map.width, map.height, &threats, &*map, 100.0);
+                destination = DijkstraMap::find_highest_exit(&flee_map, my_idx, &*map);
+            } else if let Some((target, target_pos, distance)) = closest_target {
+                // Archers take the shot when they have a clear line, otherwise close in
+                let clear_shot = ranged_weapon(entity, &entities, &ranged_weapons, &items_owned)
+                    .is_some_and(|(_, weapon)| distance <= weapon.range as f32 && line_of_fire(&map, my_pos, target_pos).last() == Some(&target_pos));
+
+                // Mobs with a damaging ability of their own (a breath, an explosion) use it now and then
+                let use_ability = abilities.get(entity).is_some_and(|reach| {
+                    inflicts_damage.get(entity).is_some()
+                        && distance <= reach.range as f32
+                        && line_of_fire(&map, my_pos, target_pos).last() == Some(&target_pos)
+                }) && rng.roll_dice(1, 3) == 1;
+
+                if use_ability {
+                    use_intent.insert(entity, UseItemIntent{ item: entity, target: Some(target_pos) }).expect("Unable to insert intent");
+                } else if distance < 1.5 {
+                    melee_intent.insert(entity, MeleeIntent{ target }).expect("Unable to insert melee intent");
+                } else if clear_shot {
+                    ranged_intent.insert(entity, RangedIntent{ target }).expect("Unable to insert ranged intent");
+                } else {
+                    let mut target_idx = map.xy_idx(target_pos.x, target_pos.y);
+                    if group.is_some() {
+                        if let Some(flank_idx) = free_flank(&map, &claimed, my_pos, target_pos) {
+                            claimed.insert(flank_idx);
+                            target_idx = flank_idx;
+                        }
+                    }
+                    destination = step_towards(&mut map, my_idx, target_idx);
+                }
+            } else if let Some(search_point) = search_point {
+                if search_point != my_pos {
+                    let search_idx = map.xy_idx(search_point.x, search_point.y);
+                    destination = step_towards(&mut map, my_idx, search_idx);
+                }
+            }
+
+            if let Some(destination) = destination {
+                if !map.blocked[destination] {
+                    map.blocked[my_idx] = false;
+                    map.blocked[destination] = true;
+                    pos.x = destination as i32 % map.width;
+                    pos.y = destination as i32 / map.width;
+                    viewshed.dirty = true;
+                    entity_moved.insert(entity, EntityMoved{}).expect("Unable to insert EntityMoved marker");
+                }
+            }
+        }
+    }
+}
+
+// Pick the closest open tile next to the target so the pack spreads around it instead of queueing
+fn free_flank(map: &Map, claimed: &HashSet<usize>, from: Point, target: Point) -> Option<usize> {
+    let mut best : Option<(usize, f32)> = None;
+    for dy in -1 ..= 1 {
+        for dx in -1 ..= 1 {
+            let flank = Point::new(target.x + dx, target.y + dy);
+            if (dx == 0 && dy == 0) || flank.x < 1 || flank.x > map.width - 2 || flank.y < 1 || flank.y > map.height - 2 { continue; }
+            let idx = map.xy_idx(flank.x, flank.y);
+            if map.blocked[idx] || claimed.contains(&idx) { continue; }
+            let distance = DistanceAlg::Pythagoras.distance2d(from, flank);
+            if best.is_none_or(|(_, d)| distance < d) {
+                best = Some((idx, distance));
+            }
+        }
+    }
+    best.map(|(idx, _)| idx)
+}
+
+// The target blocks its own tile, so let the path finish on it
+fn step_towards(map: &mut Map, from_idx: usize, to_idx: usize) -> Option<usize> {
+    let was_blocked = map.blocked[to_idx];
+    map.blocked[to_idx] = false;
+    let path = a_star_search(from_idx, to_idx, &*map);
+    map.blocked[to_idx] = was_blocked;
+
+    if path.success && path.steps.len() > 1 {
+        Some(path.steps[1])
+    } else {
+        None
+    }
+}
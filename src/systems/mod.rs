@@ -5,6 +5,13 @@ mod fov;
 mod inventory;
 mod particle;
 mod trigger;
+mod mob_ai;
+mod awareness;
+mod ranged_combat;
+mod status;
+mod attributes;
+mod hunger;
+mod throwing;
 
 pub use map_indexing::*;
 pub use melee_combat::*;
@@ -12,4 +19,11 @@ pub use damage::*;
 pub use fov::*;
 pub use inventory::*;
 pub use particle::*;
-pub use trigger::*;
\ No newline at end of file
+pub use trigger::*;
+pub use mob_ai::*;
+pub use awareness::*;
+pub use ranged_combat::*;
+pub use status::*;
+pub use attributes::*;
+pub use hunger::*;
+pub use throwing::*;
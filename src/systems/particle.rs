@@ -27,7 +27,7 @@ impl <'a> System<'a> for ParticleSpawnSystem {
                 .insert(particle, Renderable { glyph: new_particle.glyph, fg: new_particle.fg, bg: new_particle.bg, render_order: 0 })
                 .expect("Unable to insert Renderable for Particle");
             particles
-                .insert(particle, ParticleLifetime { lifetime_ms: new_particle.lifetime })
+                .insert(particle, ParticleLifetime { lifetime_ms: new_particle.lifetime, delay_ms: new_particle.delay })
                 .expect("Unable to insert ParticleLifetime for Particle");
         }
 
@@ -41,7 +41,8 @@ struct ParticleRequest {
     fg: RGB,
     bg: RGB,
     glyph: FontCharType,
-    lifetime: f32
+    lifetime: f32,
+    delay: f32
 }
 
 pub struct ParticleBuilder {
@@ -55,9 +56,18 @@ impl ParticleBuilder {
 
     pub fn request(&mut self, x: i32, y: i32, fg: RGB, bg: RGB, glyph: FontCharType, lifetime: f32) {
         self.requests.push(
-            ParticleRequest { x, y, fg, bg, glyph, lifetime }
+            ParticleRequest { x, y, fg, bg, glyph, lifetime, delay: 0.0 }
         )
     }
+
+    // One particle per tile, each showing up as the previous one fades
+    pub fn projectile(&mut self, path: &[Point], fg: RGB, glyph: FontCharType, ms_per_tile: f32) {
+        for (i, step) in path.iter().enumerate() {
+            self.requests.push(
+                ParticleRequest { x: step.x, y: step.y, fg, bg: RGB::named(BLACK), glyph, lifetime: ms_per_tile, delay: ms_per_tile * i as f32 }
+            )
+        }
+    }
 }
 
 pub fn remove_particles(ecs: &mut World, ctx: &BTerm) {
@@ -66,6 +76,10 @@ pub fn remove_particles(ecs: &mut World, ctx: &BTerm) {
         let mut particles = ecs.write_storage::<ParticleLifetime>();
         let entities = ecs.entities();
         for (entity, mut particle) in (&entities, &mut particles).join() {
+            if particle.delay_ms > 0.0 {
+                particle.delay_ms -= ctx.frame_time_ms;
+                continue;
+            }
             particle.lifetime_ms -= ctx.frame_time_ms;
             if particle.lifetime_ms < 0.0 {
                 dead_particles.push(entity);
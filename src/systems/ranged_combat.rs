@@ -0,0 +1,137 @@
+use specs::prelude::*;
+use bracket_lib::prelude::*;
+use crate::{game::GameLog, map::Map, components::*};
+
+use super::{ParticleBuilder, NoiseBuilder, AttackRoll, roll_attack};
+
+pub struct RangedCombatSystem {}
+
+impl <'a> System<'a> for RangedCombatSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Map>,
+        WriteStorage<'a, RangedIntent>,
+        ReadStorage<'a, RangedWeapon>,
+        ReadStorage<'a, ItemOwned>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, CombatStats>,
+        ReadStorage<'a, PoolStats>,
+        WriteStorage<'a, Damage>,
+        WriteExpect<'a, GameLog>,
+        WriteExpect<'a, ParticleBuilder>,
+        WriteExpect<'a, NoiseBuilder>,
+        ReadStorage<'a, Position>,
+        WriteStorage<'a, Awareness>,
+        ReadStorage<'a, InflictsCondition>,
+        WriteStorage<'a, StatusEffect>,
+        WriteExpect<'a, RandomNumberGenerator>,
+        ReadStorage<'a, Equipped>,
+        ReadStorage<'a, Wearable>,
+        ReadStorage<'a, Enchantment>,
+        ReadStorage<'a, Durability>,
+        ReadStorage<'a, Resistances>
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, map, mut ranged_intent, ranged_weapons, items_owned, names, combat_stats, pool_stats, mut inflicted_damage, mut log, mut particle_builder, mut noise, positions, mut awareness, inflicts_condition, mut status_effects, mut rng, equipped, wearables, enchantments, durability, resistances) = data;
+
+        for (entity, intent, name, stats, pool, pos) in (&entities, &ranged_intent, &names, &combat_stats, &pool_stats, &positions).join() {
+            if pool.hp.current < 1 { continue; }
+            let weapon = ranged_weapon(entity, &entities, &ranged_weapons, &items_owned);
+            let target_pos = positions.get(intent.target);
+            let (weapon_entity, weapon, target_pos) = match (weapon, target_pos) {
+                (Some((weapon_entity, weapon)), Some(target_pos)) => (weapon_entity, weapon, target_pos),
+                _ => continue
+            };
+
+            let start = Point::new(pos.x, pos.y);
+            let path = line_of_fire(&map, start, Point::new(target_pos.x, target_pos.y));
+            particle_builder.projectile(&path, RGB::named(YELLOW), to_cp437('*'), 40.0);
+
+            // Whatever stands first on the line takes the shot, walls just eat it
+            let impact = match path.last() {
+                None => continue,
+                Some(impact) => *impact
+            };
+            noise.request(impact.x, impact.y, 6);
+            let impact_idx = map.xy_idx(impact.x, impact.y);
+            let victim = map.tile_content[impact_idx].iter().find(|e| pool_stats.get(**e).is_some()).copied();
+            let victim = match victim {
+                None => {
+                    log.entries.push(format!("{}'s shot goes wide.", &name.name));
+                    continue;
+                }
+                Some(victim) => victim
+            };
+
+            let victim_name = names.get(victim).unwrap();
+            let victim_stats = combat_stats.get(victim).unwrap();
+
+            // Accuracy falls off once past half the weapon's range
+            let distance = DistanceAlg::Pythagoras.distance2d(start, impact) as i32;
+            let range_penalty = i32::max(0, distance - weapon.range / 2);
+            let defense = victim_stats.defense + armor(victim, &equipped, &wearables, &enchantments, &durability);
+            let resisted = |damage| resisted_damage(resistances.get(victim), damage, weapon.kind);
+            let damage = match roll_attack(&mut rng, stats.attack + weapon.power, -range_penalty, defense, victim_stats.evade) {
+                AttackRoll::Fumble => {
+                    log.entries.push(format!("{} fumbles the shot!", &name.name));
+                    0
+                }
+                AttackRoll::Miss => {
+                    log.entries.push(format!("{}'s shot misses {}.", &name.name, &victim_name.name));
+                    0
+                }
+                AttackRoll::Hit(damage) => {
+                    log.entries.push(format!("{} shoots {}, for {} hp.", &name.name, &victim_name.name, resisted(damage)));
+                    damage
+                }
+                AttackRoll::Critical(damage) => {
+                    log.entries.push(format!("{} shoots {}, for {} hp. Critical!", &name.name, &victim_name.name, resisted(damage)));
+                    damage
+                }
+            };
+
+            if damage > 0 {
+                Damage::new(&mut inflicted_damage, victim, damage, weapon.kind, Some(entity));
+
+                if let Some(inflicts) = inflicts_condition.get(weapon_entity) {
+                    StatusEffect::apply(&entities, &mut status_effects, victim, inflicts.condition, inflicts.turns, inflicts.potency);
+                    log.entries.push(format!("{} is {}!", &victim_name.name, inflicts.condition.name()));
+                }
+            }
+
+            if let Some(victim_awareness) = awareness.get_mut(victim) {
+                victim_awareness.set_state(AwarenessState::Hunting);
+                victim_awareness.last_seen = Some(start);
+            }
+        }
+
+        ranged_intent.clear();
+    }
+}
+
+// A mob can be a ranged weapon itself, anyone else shoots the strongest one in their pack, the longest reach breaking ties
+pub fn ranged_weapon(entity: Entity, entities: &Entities, ranged_weapons: &ReadStorage<RangedWeapon>, items_owned: &ReadStorage<ItemOwned>) -> Option<(Entity, RangedWeapon)> {
+    if let Some(weapon) = ranged_weapons.get(entity) {
+        return Some((entity, weapon.clone()));
+    }
+
+    (entities, items_owned, ranged_weapons).join()
+        .filter(|(_, owned, _)| owned.owner == entity)
+        .max_by_key(|(item, _, weapon)| (weapon.power, weapon.range, std::cmp::Reverse(item.id())))
+        .map(|(item, _, weapon)| (item, weapon.clone()))
+}
+
+// Bresenham from the shooter up to the first thing in the way: a blocking tile is hit, a wall stops the shot before it
+pub fn line_of_fire(map: &Map, from: Point, to: Point) -> Vec<Point> {
+    let mut path : Vec<Point> = Vec::new();
+    for step in line2d_bresenham(from, to).into_iter().skip(1) {
+        if step.x < 0 || step.x >= map.width || step.y < 0 || step.y >= map.height { break; }
+        let idx = map.xy_idx(step.x, step.y);
+        if map.is_opaque(idx) { break; }
+        path.push(step);
+        if map.blocked[idx] { break; }
+    }
+    path
+}
@@ -0,0 +1,99 @@
+use specs::prelude::*;
+use bracket_lib::prelude::*;
+
+use crate::{RunState, game::GameLog, map::{Map, TileType}, components::*};
+
+use super::ParticleBuilder;
+
+pub struct StatusEffectSystem {}
+
+impl<'a> System<'a> for StatusEffectSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = ( ReadExpect<'a, RunState>,
+                        ReadExpect<'a, ActiveEntity>,
+                        WriteExpect<'a, Map>,
+                        WriteExpect<'a, GameLog>,
+                        WriteExpect<'a, ParticleBuilder>,
+                        Entities<'a>,
+                        WriteStorage<'a, StatusEffect>,
+                        ReadStorage<'a, Position>,
+                        ReadStorage<'a, PoolStats>,
+                        WriteStorage<'a, Damage>,
+                        ReadStorage<'a, Upgrades>,
+                        ReadStorage<'a, Resistances>);
+
+    fn run(&mut self, data : Self::SystemData) {
+        let (runstate, active_entity, mut map, mut log, mut particle_builder, entities, mut effects, positions, pool_stats, mut inflicted_damage, upgrades, resistances) = data;
+
+        // Conditions tick once per turn
+        if *runstate != RunState::MonsterTurn { return; }
+
+        let mut expired : Vec<Entity> = Vec::new();
+        let mut burning : Vec<Point> = Vec::new();
+
+        for (effect_entity, effect) in (&entities, &mut effects).join() {
+            if !entities.is_alive(effect.target) {
+                expired.push(effect_entity);
+                continue;
+            }
+
+            let pos = positions.get(effect.target);
+            match effect.condition {
+                Condition::Poisoned => {
+                    Damage::new(&mut inflicted_damage, effect.target, effect.potency, DamageType::Poison, None);
+                    if effect.target == active_entity.target {
+                        log.entries.push(format!("Poison burns in your veins, for {} hp.", resisted_damage(resistances.get(effect.target), effect.potency, DamageType::Poison)));
+                    }
+                    if let Some(pos) = pos {
+                        particle_builder.request(pos.x, pos.y, RGB::named(GREEN), RGB::named(BLACK), to_cp437('♣'), 200.0);
+                    }
+                }
+                Condition::Burnt => {
+                    Damage::new(&mut inflicted_damage, effect.target, effect.potency, DamageType::Fire, None);
+                    if effect.target == active_entity.target {
+                        log.entries.push(format!("You burn, for {} hp.", resisted_damage(resistances.get(effect.target), effect.potency, DamageType::Fire)));
+                    }
+                    if let Some(pos) = pos {
+                        particle_builder.request(pos.x, pos.y, RGB::named(ORANGE), RGB::named(BLACK), to_cp437('▲'), 200.0);
+                        burning.push(Point::new(pos.x, pos.y));
+                    }
+                }
+                Condition::Frozen | Condition::Cursed | Condition::Confused | Condition::Afraid => {}
+            }
+
+            // A ward shakes curses off faster
+            let wards = upgrades.get(effect.target).map_or(0, |u| u.count(Upgrade::CurseWard));
+            effect.turns -= if effect.condition == Condition::Cursed { 1 + wards } else { 1 };
+            if effect.turns < 1 {
+                expired.push(effect_entity);
+                if effect.target == active_entity.target {
+                    log.entries.push(format!("You are no longer {}.", effect.condition.name()));
+                }
+            }
+        }
+
+        for effect in expired {
+            entities.delete(effect).expect("Unable to delete StatusEffect");
+        }
+
+        // Fire spreads through dry grass next to anything burning, catching whoever stands in it
+        for origin in burning {
+            for dy in -1 ..= 1 {
+                for dx in -1 ..= 1 {
+                    let (x, y) = (origin.x + dx, origin.y + dy);
+                    if x < 1 || x > map.width - 2 || y < 1 || y > map.height - 2 { continue; }
+                    let idx = map.xy_idx(x, y);
+                    if !map.is_flammable(idx) { continue; }
+
+                    map.tiles[idx] = TileType::Floor;
+                    particle_builder.request(x, y, RGB::named(ORANGE), RGB::named(BLACK), to_cp437('▲'), 300.0);
+                    for victim in map.tile_content[idx].iter() {
+                        if pool_stats.get(*victim).is_some() {
+                            StatusEffect::apply(&entities, &mut effects, *victim, Condition::Burnt, 3, 1);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use specs::prelude::*;
+use bracket_lib::prelude::*;
+use crate::{game::GameLog, map::Map, components::*};
+
+use super::{ParticleBuilder, AttackRoll, roll_attack, line_of_fire};
+
+pub struct ThrowItemSystem {}
+
+impl <'a> System<'a> for ThrowItemSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Map>,
+        WriteExpect<'a, GameLog>,
+        WriteExpect<'a, ParticleBuilder>,
+        WriteExpect<'a, RandomNumberGenerator>,
+        WriteStorage<'a, ThrowItemIntent>,
+        ReadStorage<'a, Name>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, ItemOwned>,
+        ReadStorage<'a, Renderable>,
+        ReadStorage<'a, Shatters>,
+        WriteStorage<'a, AreaOfEffect>,
+        WriteStorage<'a, UseItemIntent>,
+        ReadStorage<'a, CombatStats>,
+        ReadStorage<'a, PoolStats>,
+        WriteStorage<'a, Damage>,
+        ReadStorage<'a, Equipped>,
+        ReadStorage<'a, MeleeWeapon>,
+        ReadStorage<'a, Wearable>,
+        ReadStorage<'a, Enchantment>,
+        ReadStorage<'a, Durability>,
+        ReadStorage<'a, Resistances>
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, map, mut log, mut particle_builder, mut rng, mut throw_intent, names, mut positions, mut items_owned, renderables, shatters, mut aoe, mut use_intent, combat_stats, pool_stats, mut inflicted_damage, equipped, melee_weapons, wearables, enchantments, durability, resistances) = data;
+
+        for (entity, intent, name, stats) in (&entities, &throw_intent, &names, &combat_stats).join() {
+            let start = match positions.get(entity) {
+                None => continue,
+                Some(pos) => Point::new(pos.x, pos.y)
+            };
+
+            // The item flies until something stops it, and drops at the thrower's feet if nothing is in reach
+            let path = line_of_fire(&map, start, intent.target);
+            let landing = path.last().copied().unwrap_or(start);
+            let (fg, glyph) = renderables.get(intent.item).map_or((RGB::named(WHITE), to_cp437('*')), |r| (r.fg, r.glyph));
+            particle_builder.projectile(&path, fg, glyph, 40.0);
+
+            let item_name = names.get(intent.item).map_or("item", |n| n.name.as_str());
+            let landing_idx = map.xy_idx(landing.x, landing.y);
+            let victim = map.tile_content[landing_idx].iter().find(|e| **e != entity && pool_stats.get(**e).is_some()).copied();
+            items_owned.remove(intent.item);
+
+            // Potions burst over the landing spot and go through the usual item effects
+            if shatters.get(intent.item).is_some() {
+                log.entries.push(format!("{} throws the {}, and it shatters!", &name.name, item_name));
+                aoe.insert(intent.item, AreaOfEffect{ radius: 1 }).expect("Unable to insert AreaOfEffect");
+                use_intent.insert(entity, UseItemIntent{ item: intent.item, target: Some(landing) }).expect("Unable to insert intent");
+                continue;
+            }
+
+            positions.insert(intent.item, Position{ x: landing.x, y: landing.y }).expect("Unable to insert Position");
+            let (victim, victim_stats) = match victim.and_then(|v| combat_stats.get(v).map(|s| (v, s))) {
+                None => {
+                    log.entries.push(format!("{} throws the {}.", &name.name, item_name));
+                    continue;
+                }
+                Some(victim) => victim
+            };
+            let victim_name = names.get(victim).map_or("something", |n| n.name.as_str());
+
+            // Only a blade does real harm in flight, and it is harder to land than a proper swing
+            let weapon = match melee_weapons.get(intent.item) {
+                None => {
+                    log.entries.push(format!("{} throws the {}, and it bounces off {}.", &name.name, item_name, victim_name));
+                    continue;
+                }
+                Some(weapon) => weapon
+            };
+            let attack = stats.attack + gear_value(weapon.power, enchantments.get(intent.item), durability.get(intent.item));
+            let defense = victim_stats.defense + armor(victim, &equipped, &wearables, &enchantments, &durability);
+            let resisted = |damage| resisted_damage(resistances.get(victim), damage, DamageType::Physical);
+            let damage = match roll_attack(&mut rng, attack, -2, defense, victim_stats.evade) {
+                AttackRoll::Fumble | AttackRoll::Miss => {
+                    log.entries.push(format!("{} throws the {}, but misses {}.", &name.name, item_name, victim_name));
+                    0
+                }
+                AttackRoll::Hit(damage) => {
+                    log.entries.push(format!("{} throws the {} at {}, for {} hp.", &name.name, item_name, victim_name, resisted(damage)));
+                    damage
+                }
+                AttackRoll::Critical(damage) => {
+                    log.entries.push(format!("{} throws the {} at {}, for {} hp. Critical!", &name.name, item_name, victim_name, resisted(damage)));
+                    damage
+                }
+            };
+            if damage > 0 {
+                Damage::new(&mut inflicted_damage, victim, damage, DamageType::Physical, Some(entity));
+            }
+        }
+
+        throw_intent.clear();
+    }
+}
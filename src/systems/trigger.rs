@@ -1,6 +1,6 @@
 use specs::prelude::*;
 use bracket_lib::prelude::*;
-use crate::{components::{EntityMoved, Position, EntryTrigger, Hidden, Name, InflictsDamage, Damage}, map::Map, game::GameLog};
+use crate::{components::{EntityMoved, Position, EntryTrigger, Hidden, Name, InflictsDamage, Damage, InflictsCondition, StatusEffect}, map::Map, game::GameLog};
 
 use super::ParticleBuilder;
 
@@ -18,10 +18,12 @@ impl<'a> System<'a> for TriggerSystem {
                         WriteStorage<'a, Damage>,
                         WriteExpect<'a, ParticleBuilder>,
                         Entities<'a>,
-                        WriteExpect<'a, GameLog>);
+                        WriteExpect<'a, GameLog>,
+                        ReadStorage<'a, InflictsCondition>,
+                        WriteStorage<'a, StatusEffect>);
 
     fn run(&mut self, data : Self::SystemData) {
-        let (map, mut entity_moved, position, entry_trigger, mut hidden, names, inflicts_damage, mut inflicted_damage, mut particle_builder, entities, mut log) = data;
+        let (map, mut entity_moved, position, entry_trigger, mut hidden, names, inflicts_damage, mut inflicted_damage, mut particle_builder, entities, mut log, inflicts_condition, mut status_effects) = data;
 
         // Iterate the entities that moved and their final position
         for (entity, mut _entity_moved, pos) in (&entities, &mut entity_moved, &position).join() {
@@ -41,7 +43,14 @@ impl<'a> System<'a> for TriggerSystem {
                             let damage = inflicts_damage.get(*entity_id);
                             if let Some(damage) = damage {
                                 particle_builder.request(pos.x, pos.y, RGB::named(ORANGE), RGB::named(BLACK), to_cp437('‼'), 200.0);
-                                Damage::new(&mut inflicted_damage, entity, damage.amount);                                
+                                Damage::new(&mut inflicted_damage, entity, damage.amount, damage.kind, Some(*entity_id));
+                            }
+
+                            if let Some(inflicts) = inflicts_condition.get(*entity_id) {
+                                StatusEffect::apply(&entities, &mut status_effects, entity, inflicts.condition, inflicts.turns, inflicts.potency);
+                                if let Some(victim) = names.get(entity) {
+                                    log.entries.push(format!("{} is {}!", &victim.name, inflicts.condition.name()));
+                                }
                             }
 
                             hidden.remove(*entity_id); // The trap is no longer hidden